@@ -1,6 +1,9 @@
+use crate::NodeId;
+
 ///
 /// Describes all the possible ways to remove a Node from a Tree.
 ///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum RemoveBehavior {
     ///
     /// All children of the removed Node will be dropped from the Tree.  All children (and all
@@ -19,4 +22,77 @@ pub enum RemoveBehavior {
     /// Dropped.  This is faster than `DropChildren` but doesn't free up any space inside the Tree.
     ///
     OrphanChildren,
+
+    ///
+    /// All children of the removed Node take its place in its parent's list of children, keeping
+    /// their order.
+    ///
+    /// If the removed Node is the root, its only child becomes the new root; the removal fails if
+    /// the root has more than one child.  If the removed Node is an orphan, its children are
+    /// orphaned.
+    ///
+    PromoteChildren,
+
+    ///
+    /// All children of the removed Node are appended, in order, to the children of the given Node.
+    ///
+    /// The removal fails if the given Node doesn't exist or is the removed Node or one of its
+    /// descendants.
+    ///
+    ReparentChildrenTo(NodeId),
+}
+
+///
+/// Describes all the possible places a Node can be put among the children of a parent Node.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Position {
+    ///
+    /// The Node becomes the first child of the parent.
+    ///
+    First,
+
+    ///
+    /// The Node becomes the last child of the parent.
+    ///
+    Last,
+
+    ///
+    /// The Node is placed immediately before the given sibling, which must be a child of the
+    /// parent.
+    ///
+    Before(NodeId),
+
+    ///
+    /// The Node is placed immediately after the given sibling, which must be a child of the
+    /// parent.
+    ///
+    After(NodeId),
+
+    ///
+    /// The Node ends up at the given index in the parent's children.  An index equal to the
+    /// number of children puts the Node last.
+    ///
+    Index(usize),
+}
+
+///
+/// Describes the orders in which the Nodes of a sub-tree can be visited.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Order {
+    ///
+    /// Depth-first, each Node before its children.
+    ///
+    Pre,
+
+    ///
+    /// Depth-first, each Node after its children.
+    ///
+    Post,
+
+    ///
+    /// Breadth-first, every Node at one depth before the Nodes at the next depth.
+    ///
+    Level,
 }
@@ -3,6 +3,25 @@ use crate::node::Node;
 use crate::slab::{self, Slab};
 use snowflake::ProcessUniqueId;
 
+///
+/// An iterator over every Node<T> of a CoreTree along with its NodeId, in slab order.
+///
+pub(crate) struct Iter<'a, T> {
+    id: ProcessUniqueId,
+    nodes: slab::Iter<'a, Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (NodeId, &'a Node<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let tree_id = self.id;
+        self.nodes
+            .next()
+            .map(|(index, node)| (NodeId { tree_id, index }, node))
+    }
+}
+
 ///
 /// A wrapper around a Slab containing Node<T> values.
 ///
@@ -26,6 +45,21 @@ impl<T> CoreTree<T> {
         self.slab.capacity()
     }
 
+    pub(crate) fn len(&self) -> usize {
+        self.slab.len()
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            id: self.id,
+            nodes: self.slab.iter(),
+        }
+    }
+
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.slab.reserve(additional);
+    }
+
     pub(crate) fn insert(&mut self, data: T) -> NodeId {
         let key = self.slab.insert(Node::new(data));
         self.new_node_id(key)
@@ -145,6 +179,13 @@ impl<T> CoreTree<T> {
     pub(crate) fn shrink_to_fit(&mut self) {
         self.slab.shrink_to_fit();
     }
+
+    pub(crate) fn into_nodes(self) -> impl Iterator<Item = (NodeId, Node<T>)> {
+        let id = self.id;
+        self.slab
+            .into_entries()
+            .map(move |(index, node)| (NodeId { tree_id: id, index }, node))
+    }
 }
 
 #[cfg_attr(tarpaulin, skip)]
@@ -206,6 +247,35 @@ mod tests {
         assert_eq!(tree.get_mut(id2).unwrap().data, 3);
     }
 
+    #[test]
+    fn iter() {
+        let mut tree = CoreTree::new(0);
+        let id = tree.insert(1);
+        let id2 = tree.insert(3);
+        let id3 = tree.insert(5);
+        tree.remove(id2);
+
+        let nodes: Vec<_> = tree
+            .iter()
+            .map(|(node_id, node)| (node_id, node.data))
+            .collect();
+        assert_eq!(nodes, vec![(id, 1), (id3, 5)]);
+        assert_eq!(tree.len(), 2);
+    }
+
+    #[test]
+    fn into_nodes() {
+        let mut tree = CoreTree::new(0);
+        let id = tree.insert(1);
+        let id2 = tree.insert(3);
+
+        let nodes: Vec<_> = tree
+            .into_nodes()
+            .map(|(node_id, node)| (node_id, node.data))
+            .collect();
+        assert_eq!(nodes, vec![(id, 1), (id2, 3)]);
+    }
+
     #[test]
     fn get_with_bad_id() {
         let mut tree = CoreTree::new(0);
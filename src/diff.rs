@@ -0,0 +1,1128 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Write};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::IdMap;
+use crate::NodeId;
+use crate::behaviors::{Order, Position, RemoveBehavior};
+use crate::error::{MoveError, PatchError};
+use crate::tree::Tree;
+
+///
+/// A single step of an edit script turning one `Tree` into another.
+///
+/// `NodeId`s name `Node`s of the old `Tree`, except for `Node`s added by an `Insert`, which are
+/// named by their `NodeId` in the new `Tree` from then on.  Indexes are positions among the
+/// parent's children once the `Edit` is applied.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Edit<T> {
+    ///
+    /// Adds a `Node` holding `data` at `index` among the children of `parent_id`, or as the root
+    /// if there is no parent.  `node_id` is the `NodeId` of the `Node` in the new `Tree`.
+    ///
+    Insert {
+        node_id: NodeId,
+        parent_id: Option<NodeId>,
+        index: usize,
+        data: T,
+    },
+
+    ///
+    /// Removes a `Node` along with its descendants, none of which are part of the new `Tree`.
+    ///
+    Delete { node_id: NodeId },
+
+    ///
+    /// Replaces the data of a `Node`.
+    ///
+    Update { node_id: NodeId, data: T },
+
+    ///
+    /// Moves a `Node` (and its descendants) to `index` among the children of another parent.
+    ///
+    Move {
+        node_id: NodeId,
+        parent_id: NodeId,
+        index: usize,
+    },
+
+    ///
+    /// Moves a `Node` (and its descendants) to `index` among the children of its current parent.
+    ///
+    Reorder { node_id: NodeId, index: usize },
+}
+
+///
+/// The differences between two `Tree`s, as returned by `Tree::diff`: which `Node`s of the old
+/// `Tree` match which `Node`s of the new one, and an edit script turning the old `Tree` into the
+/// new one.
+///
+/// The number of `Edit`s is an edit distance between the two `Tree`s.  It is found heuristically
+/// and isn't always the smallest possible one.
+///
+#[derive(Debug)]
+pub struct Diff<'a, T> {
+    old: &'a Tree<T>,
+    new: &'a Tree<T>,
+    matching: IdMap,
+    edits: Vec<Edit<T>>,
+}
+
+impl<'a, T> Diff<'a, T> {
+    ///
+    /// Returns the edit script turning the old `Tree` into the new one.
+    ///
+    pub fn edits(&self) -> &[Edit<T>] {
+        &self.edits
+    }
+
+    ///
+    /// Returns the edit script turning the old `Tree` into the new one, to be passed on to
+    /// `Tree::apply_patch`.
+    ///
+    pub fn into_edits(self) -> Vec<Edit<T>> {
+        self.edits
+    }
+
+    ///
+    /// Returns the number of `Edit`s in the edit script.
+    ///
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    ///
+    /// Returns `true` if the two `Tree`s are equal.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    ///
+    /// Returns a map from the `NodeId`s of the `Node`s of the old `Tree` that are kept (possibly
+    /// updated or moved) to the `NodeId`s of the matching `Node`s of the new `Tree`.
+    ///
+    pub fn matching(&self) -> &IdMap {
+        &self.matching
+    }
+}
+
+impl<T: Display> Diff<'_, T> {
+    ///
+    /// Writes the new `Tree` the way `Tree::write_formatted` does, with the deleted `Node`s of the
+    /// old `Tree` shown where they used to be.  Each line starts with a marker: `+` for an
+    /// inserted `Node`, `-` for a deleted one, `>` for a moved or reordered one, `~` for one that
+    /// was only updated and a space for an unchanged one.  Updated data is written as
+    /// `old -> new`.
+    ///
+    /// ```
+    /// use nary_tree::tree;
+    ///
+    /// let old = tree!(0 => [1 => [2, 3], 4, 5]);
+    /// let new = tree!(0 => [1 => [2, 3], 6, 7 => [5]]);
+    ///
+    /// let mut s = String::new();
+    /// old.diff(&new).write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "  \
+    ///   0
+    ///   ├── 1
+    ///   │   ├── 2
+    ///   │   └── 3
+    /// ~ ├── 4 -> 6
+    /// + └── 7
+    /// >     └── 5
+    /// ");
+    /// ```
+    ///
+    pub fn write_formatted<W: Write>(&self, w: &mut W) -> fmt::Result {
+        let top = match (self.new.root_id, self.old.root_id) {
+            (Some(root_id), _) => Row::New(root_id),
+            (None, Some(root_id)) => Row::Deleted(root_id),
+            (None, None) => return Ok(()),
+        };
+        let mut moved = HashSet::new();
+        let mut updated = HashSet::new();
+        for edit in &self.edits {
+            match edit {
+                Edit::Move { node_id, .. } | Edit::Reorder { node_id, .. } => {
+                    moved.insert(*node_id);
+                }
+                Edit::Update { node_id, .. } => {
+                    updated.insert(*node_id);
+                }
+                Edit::Insert { .. } | Edit::Delete { .. } => {}
+            }
+        }
+        let new_to_old: IdMap = self.matching.iter().map(|(&o, &n)| (n, o)).collect();
+
+        let mut stack = vec![(top, vec![])];
+        while let Some((row, last)) = stack.pop() {
+            let (marker, text) = match row {
+                Row::New(node_id) => {
+                    let data = self.new.get(node_id).expect("row of the new tree").data();
+                    match new_to_old.get(&node_id) {
+                        None => ('+', data.to_string()),
+                        Some(&old_id) => {
+                            let old_data = self.old.get(old_id).expect("matched node").data();
+                            let is_updated = updated.contains(&old_id);
+                            let marker = if moved.contains(&old_id) {
+                                '>'
+                            } else if is_updated {
+                                '~'
+                            } else {
+                                ' '
+                            };
+                            if is_updated {
+                                (marker, format!("{old_data} -> {data}"))
+                            } else {
+                                (marker, data.to_string())
+                            }
+                        }
+                    }
+                }
+                Row::Deleted(node_id) => {
+                    let data = self.old.get(node_id).expect("row of the old tree").data();
+                    ('-', data.to_string())
+                }
+            };
+
+            let mut lines = text.lines();
+            let first = lines.next().unwrap_or("");
+            writeln!(w, "{marker} {}{first}", prefix(&last, true))?;
+            for line in lines {
+                writeln!(w, "{marker} {}{line}", prefix(&last, false))?;
+            }
+
+            let children = self.rows_below(row, &new_to_old);
+            let count = children.len();
+            for (i, child) in children.into_iter().enumerate().rev() {
+                let mut child_last = last.clone();
+                child_last.push(i + 1 == count);
+                stack.push((child, child_last));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the rows written below `row`: the children of a `Node` of the new `Tree`, with the
+    /// deleted children of its match slotted in after their closest kept left sibling, or the
+    /// deleted children of a deleted `Node`.
+    fn rows_below(&self, row: Row, new_to_old: &IdMap) -> Vec<Row> {
+        match row {
+            Row::New(node_id) => {
+                let mut deleted_after: HashMap<Option<NodeId>, Vec<NodeId>> = HashMap::new();
+                if let Some(&old_id) = new_to_old.get(&node_id) {
+                    let mut after = None;
+                    for child_id in child_ids(self.old, old_id) {
+                        match self.matching.get(&child_id) {
+                            Some(&new_id) => {
+                                if self.new.get_node_relatives(new_id).parent == Some(node_id) {
+                                    after = Some(new_id);
+                                }
+                            }
+                            None => deleted_after.entry(after).or_default().push(child_id),
+                        }
+                    }
+                }
+                let mut rows: Vec<Row> = deleted_after
+                    .remove(&None)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(Row::Deleted)
+                    .collect();
+                for child_id in child_ids(self.new, node_id) {
+                    rows.push(Row::New(child_id));
+                    if let Some(deleted) = deleted_after.remove(&Some(child_id)) {
+                        rows.extend(deleted.into_iter().map(Row::Deleted));
+                    }
+                }
+                rows
+            }
+            Row::Deleted(node_id) => child_ids(self.old, node_id)
+                .into_iter()
+                .filter(|child_id| !self.matching.contains_key(child_id))
+                .map(Row::Deleted)
+                .collect(),
+        }
+    }
+}
+
+impl<T: Display> Display for Diff<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_formatted(f)
+    }
+}
+
+/// A line of a rendered `Diff`: a `Node` of the new `Tree` or a deleted `Node` of the old one.
+#[derive(Copy, Clone)]
+enum Row {
+    New(NodeId),
+    Deleted(NodeId),
+}
+
+/// Returns the box-drawing prefix of the first or a following line of a `Node`, given whether
+/// each of its ancestors (and itself) is the last of its siblings.
+fn prefix(last: &[bool], first_line: bool) -> String {
+    let mut prefix = String::new();
+    if let Some((&is_last, ancestors)) = last.split_last() {
+        for &ancestor_is_last in ancestors {
+            prefix.push_str(if ancestor_is_last { "    " } else { "│   " });
+        }
+        prefix.push_str(match (first_line, is_last) {
+            (true, true) => "└── ",
+            (true, false) => "├── ",
+            (false, true) => "    ",
+            (false, false) => "│   ",
+        });
+    }
+    prefix
+}
+
+impl<T: Clone + Eq + Hash> Tree<T> {
+    ///
+    /// Compares this `Tree` with `other` and returns their differences as a `Diff`, whose edit
+    /// script turns this `Tree` into `other` when passed to `apply_patch`.  Only the rooted
+    /// `Tree`s are compared; orphaned `Node`s are ignored.
+    ///
+    /// `Node`s are matched in the manner of GumTree: identical sub-trees are matched first,
+    /// largest first, then `Node`s holding equal data and sharing most of their matched
+    /// descendants, and finally the remaining children of matched `Node`s, by equal data and then
+    /// in order.  The roots always match each other.  The edit script is then derived from the
+    /// matching.
+    ///
+    /// ```
+    /// use nary_tree::diff::Edit;
+    /// use nary_tree::tree;
+    ///
+    /// tree!(let mut old = 0 => [one @ 1 => [2], three @ 3]);
+    /// let new = tree!(0 => [3 => [1 => [2]]]);
+    ///
+    /// let diff = old.diff(&new);
+    /// assert_eq!(
+    ///     diff.edits(),
+    ///     &[Edit::Move { node_id: one, parent_id: three, index: 0 }]
+    /// );
+    ///
+    /// old.apply_patch(diff.into_edits()).unwrap();
+    /// assert_eq!(old, new);
+    /// ```
+    ///
+    pub fn diff<'a>(&'a self, other: &'a Tree<T>) -> Diff<'a, T> {
+        let mut matcher = Matcher::new(self, other);
+        matcher.match_identical();
+        matcher.match_containers();
+        matcher.match_remaining_children();
+        let edits = Script::new(&matcher).generate();
+        Diff {
+            old: self,
+            new: other,
+            matching: matcher.old_to_new,
+            edits,
+        }
+    }
+}
+
+impl<T> Tree<T> {
+    ///
+    /// Applies a list of `Edit`s, such as the edit script of a `Diff` of this `Tree`, in order.
+    /// Returns a map from the `NodeId`s named by `Insert`s to the `NodeId`s of the inserted
+    /// `Node`s.
+    ///
+    /// Returns a `PatchError` if an `Edit` names a `Node` that doesn't exist or can't be applied
+    /// where it asks to.  The `Edit`s before it stay applied.
+    ///
+    /// ```
+    /// use nary_tree::diff::Edit;
+    /// use nary_tree::tree;
+    ///
+    /// tree!(let mut tree = root @ 0 => [one @ 1, 2]);
+    /// let other = tree!(5);
+    /// let new_id = other.root_id().unwrap();
+    ///
+    /// let inserted = tree
+    ///     .apply_patch(vec![
+    ///         Edit::Insert { node_id: new_id, parent_id: Some(root), index: 1, data: 5 },
+    ///         Edit::Reorder { node_id: one, index: 2 },
+    ///         Edit::Update { node_id: root, data: 10 },
+    ///     ])
+    ///     .unwrap();
+    /// assert_eq!(tree.get(inserted[&new_id]).unwrap().data(), &5);
+    /// assert_eq!(tree, tree!(10 => [5, 2, 1]));
+    /// ```
+    ///
+    pub fn apply_patch<I>(&mut self, edits: I) -> Result<IdMap, PatchError>
+    where
+        I: IntoIterator<Item = Edit<T>>,
+    {
+        let mut inserted = IdMap::new();
+        for edit in edits {
+            match edit {
+                Edit::Insert {
+                    node_id,
+                    parent_id,
+                    index,
+                    data,
+                } => {
+                    let new_id = match parent_id {
+                        Some(parent_id) => {
+                            let parent_id = self.resolve(&inserted, parent_id)?;
+                            let position = self
+                                .check_position(parent_id, None, Position::Index(index))
+                                .map_err(PatchError::Move)?;
+                            let new_id = self.core_tree.insert(data);
+                            self.link_at(new_id, parent_id, position);
+                            new_id
+                        }
+                        None if self.root_id.is_some() => return Err(PatchError::RootExists),
+                        None => self.set_root(data),
+                    };
+                    inserted.insert(node_id, new_id);
+                }
+                Edit::Delete { node_id } => {
+                    let node_id = self.resolve(&inserted, node_id)?;
+                    self.remove(node_id, RemoveBehavior::DropChildren);
+                }
+                Edit::Update { node_id, data } => {
+                    let node_id = self.resolve(&inserted, node_id)?;
+                    *self.get_mut(node_id).expect("node was resolved").data() = data;
+                }
+                Edit::Move {
+                    node_id,
+                    parent_id,
+                    index,
+                } => {
+                    let node_id = self.resolve(&inserted, node_id)?;
+                    let parent_id = self.resolve(&inserted, parent_id)?;
+                    self.get_mut(node_id)
+                        .expect("node was resolved")
+                        .move_to(parent_id, Position::Index(index))
+                        .map_err(PatchError::Move)?;
+                }
+                Edit::Reorder { node_id, index } => {
+                    let node_id = self.resolve(&inserted, node_id)?;
+                    let parent_id = self
+                        .get_node_relatives(node_id)
+                        .parent
+                        .ok_or(PatchError::Move(MoveError::RootNotMovable))?;
+                    self.get_mut(node_id)
+                        .expect("node was resolved")
+                        .move_to(parent_id, Position::Index(index))
+                        .map_err(PatchError::Move)?;
+                }
+            }
+        }
+        Ok(inserted)
+    }
+
+    /// Returns the `NodeId` an `Edit` refers to by `node_id`, which is translated if it names an
+    /// inserted `Node`.
+    fn resolve(&self, inserted: &IdMap, node_id: NodeId) -> Result<NodeId, PatchError> {
+        let resolved = inserted.get(&node_id).copied().unwrap_or(node_id);
+        match self.get_node(resolved) {
+            Some(_) => Ok(resolved),
+            None => Err(PatchError::NodeNotFound(node_id)),
+        }
+    }
+}
+
+fn child_ids<T>(tree: &Tree<T>, node_id: NodeId) -> Vec<NodeId> {
+    tree.get(node_id)
+        .map(|node| node.children().map(|child| child.node_id()).collect())
+        .unwrap_or_default()
+}
+
+/// Returns the `NodeId`s of the rooted `Tree` in the given `Order`.
+fn rooted_ids<T>(tree: &Tree<T>, order: Order) -> Vec<NodeId> {
+    tree.root_id
+        .map(|root_id| {
+            tree.subtree_ids(root_id, order)
+                .into_iter()
+                .map(|(_, node_id)| node_id)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// What the matcher needs to know about a sub-tree.
+#[derive(Copy, Clone)]
+struct Summary {
+    hash: u64,
+    height: usize,
+    descendants: usize,
+}
+
+/// Computes the `Summary` of every sub-tree of the rooted `Tree`.
+fn summarize<T: Hash>(tree: &Tree<T>) -> HashMap<NodeId, Summary> {
+    let mut summaries: HashMap<NodeId, Summary> = HashMap::new();
+    // reversed pre-order visits every `Node` after its descendants
+    for node_id in rooted_ids(tree, Order::Pre).into_iter().rev() {
+        let mut hasher = DefaultHasher::new();
+        tree.get(node_id)
+            .expect("rooted node")
+            .data()
+            .hash(&mut hasher);
+        let mut height = 0;
+        let mut descendants = 0;
+        for child_id in child_ids(tree, node_id) {
+            let child = summaries[&child_id];
+            child.hash.hash(&mut hasher);
+            height = height.max(child.height + 1);
+            descendants += child.descendants + 1;
+        }
+        let hash = hasher.finish();
+        summaries.insert(
+            node_id,
+            Summary {
+                hash,
+                height,
+                descendants,
+            },
+        );
+    }
+    summaries
+}
+
+/// Returns the indexes of a longest strictly increasing subsequence of `values`, in order.  This
+/// is patience sorting, so it takes O(n log n) time and O(n) space however long `values` is.
+fn longest_increasing(values: &[usize]) -> Vec<usize> {
+    // `tails[k]` is the index of the smallest value ending an increasing run of length `k + 1`
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev: Vec<Option<usize>> = Vec::with_capacity(values.len());
+    for (i, &value) in values.iter().enumerate() {
+        let k = tails.partition_point(|&tail| values[tail] < value);
+        prev.push(k.checked_sub(1).map(|k| tails[k]));
+        if k == tails.len() {
+            tails.push(i);
+        } else {
+            tails[k] = i;
+        }
+    }
+    let mut indexes = Vec::with_capacity(tails.len());
+    let mut next = tails.last().copied();
+    while let Some(i) = next {
+        indexes.push(i);
+        next = prev[i];
+    }
+    indexes.reverse();
+    indexes
+}
+
+/// Matches the `Node`s of an old and a new `Tree`.
+struct Matcher<'a, T> {
+    old: &'a Tree<T>,
+    new: &'a Tree<T>,
+    old_summaries: HashMap<NodeId, Summary>,
+    new_summaries: HashMap<NodeId, Summary>,
+    old_to_new: IdMap,
+    new_to_old: IdMap,
+}
+
+impl<'a, T: Eq + Hash> Matcher<'a, T> {
+    fn new(old: &'a Tree<T>, new: &'a Tree<T>) -> Matcher<'a, T> {
+        Matcher {
+            old,
+            new,
+            old_summaries: summarize(old),
+            new_summaries: summarize(new),
+            old_to_new: IdMap::new(),
+            new_to_old: IdMap::new(),
+        }
+    }
+
+    fn link(&mut self, old_id: NodeId, new_id: NodeId) {
+        self.old_to_new.insert(old_id, new_id);
+        self.new_to_old.insert(new_id, old_id);
+    }
+
+    fn link_subtrees(&mut self, old_id: NodeId, new_id: NodeId) {
+        let old_ids = self.old.subtree_ids(old_id, Order::Pre);
+        let new_ids = self.new.subtree_ids(new_id, Order::Pre);
+        for ((_, old_id), (_, new_id)) in old_ids.into_iter().zip(new_ids) {
+            self.link(old_id, new_id);
+        }
+    }
+
+    fn is_identical(&self, old_id: NodeId, new_id: NodeId) -> bool {
+        self.old_summaries[&old_id].hash == self.new_summaries[&new_id].hash
+            && self
+                .old
+                .get(old_id)
+                .expect("rooted node")
+                .structurally_eq(&self.new.get(new_id).expect("rooted node"))
+    }
+
+    /// Links the roots, and then identical sub-trees from the tallest down.  A leaf is only
+    /// linked when its data is unique in both `Tree`s, since there is nothing else to tell equal
+    /// leaves apart by.
+    fn match_identical(&mut self) {
+        let (Some(old_root), Some(new_root)) = (self.old.root_id, self.new.root_id) else {
+            return;
+        };
+        if self.is_identical(old_root, new_root) {
+            self.link_subtrees(old_root, new_root);
+            return;
+        }
+        self.link(old_root, new_root);
+
+        let mut candidates: HashMap<u64, Vec<NodeId>> = HashMap::new();
+        for old_id in rooted_ids(self.old, Order::Pre).into_iter().skip(1) {
+            let hash = self.old_summaries[&old_id].hash;
+            candidates.entry(hash).or_default().push(old_id);
+        }
+        let mut new_counts: HashMap<u64, usize> = HashMap::new();
+        let mut new_ids = rooted_ids(self.new, Order::Pre).split_off(1);
+        for new_id in &new_ids {
+            *new_counts
+                .entry(self.new_summaries[new_id].hash)
+                .or_default() += 1;
+        }
+        new_ids.sort_by_key(|new_id| std::cmp::Reverse(self.new_summaries[new_id].height));
+
+        for new_id in new_ids {
+            if self.new_to_old.contains_key(&new_id) {
+                continue;
+            }
+            let summary = self.new_summaries[&new_id];
+            let Some(old_ids) = candidates.get(&summary.hash) else {
+                continue;
+            };
+            if summary.height == 0 && (old_ids.len() != 1 || new_counts[&summary.hash] != 1) {
+                continue;
+            }
+            let new_parent = self.new.get_node_relatives(new_id).parent;
+            let matching_parent = new_parent.and_then(|parent| self.new_to_old.get(&parent));
+            let mut unmatched = old_ids
+                .iter()
+                .copied()
+                .filter(|old_id| !self.old_to_new.contains_key(old_id))
+                .filter(|&old_id| self.is_identical(old_id, new_id));
+            let first = unmatched.next();
+            let preferred = first.into_iter().chain(unmatched).find(|&old_id| {
+                self.old.get_node_relatives(old_id).parent.as_ref() == matching_parent
+            });
+            if let Some(old_id) = preferred.or(first) {
+                self.link_subtrees(old_id, new_id);
+            }
+        }
+    }
+
+    /// Links each unmatched `Node` of the new `Tree` with the unmatched `Node` of the old `Tree`
+    /// holding equal data and the most of the matches of its descendants, if they share at least
+    /// half of their descendants.
+    fn match_containers(&mut self) {
+        // reversed pre-order visits every `Node` after its descendants
+        for new_id in rooted_ids(self.new, Order::Pre).into_iter().rev() {
+            let new_descendants = self.new_summaries[&new_id].descendants;
+            if self.new_to_old.contains_key(&new_id) || new_descendants == 0 {
+                continue;
+            }
+            let data = self.new.get(new_id).expect("rooted node").data();
+            let mut counts: HashMap<NodeId, usize> = HashMap::new();
+            let mut candidates = Vec::new();
+            for (_, descendant_id) in self.new.subtree_ids(new_id, Order::Pre).into_iter().skip(1) {
+                let Some(&old_id) = self.new_to_old.get(&descendant_id) else {
+                    continue;
+                };
+                let old_node = self.old.get(old_id).expect("rooted node");
+                for ancestor in old_node.ancestors() {
+                    let ancestor_id = ancestor.node_id();
+                    if self.old_to_new.contains_key(&ancestor_id) || ancestor.data() != data {
+                        continue;
+                    }
+                    let count = counts.entry(ancestor_id).or_insert(0);
+                    if *count == 0 {
+                        candidates.push(ancestor_id);
+                    }
+                    *count += 1;
+                }
+            }
+            let best = candidates
+                .into_iter()
+                .rev()
+                .max_by_key(|candidate| counts[candidate]);
+            if let Some(old_id) = best {
+                let common = counts[&old_id];
+                let total = self.old_summaries[&old_id].descendants + new_descendants;
+                // a dice coefficient (2 * common / total) of at least a half
+                if 4 * common >= total {
+                    self.link(old_id, new_id);
+                }
+            }
+        }
+    }
+
+    /// Links the unmatched children of matched `Node`s, first those holding equal data (in any
+    /// order) and then the rest, in order.
+    fn match_remaining_children(&mut self) {
+        for new_id in rooted_ids(self.new, Order::Pre) {
+            let Some(&old_id) = self.new_to_old.get(&new_id) else {
+                continue;
+            };
+            let mut old_children: Vec<NodeId> = child_ids(self.old, old_id)
+                .into_iter()
+                .filter(|child_id| !self.old_to_new.contains_key(child_id))
+                .collect();
+            let mut new_children: Vec<NodeId> = child_ids(self.new, new_id)
+                .into_iter()
+                .filter(|child_id| !self.new_to_old.contains_key(child_id))
+                .collect();
+            for &new_child in &new_children {
+                let data = self.new.get(new_child).expect("rooted node").data();
+                let equal = old_children.iter().position(|&old_child| {
+                    !self.old_to_new.contains_key(&old_child)
+                        && self.old.get(old_child).expect("rooted node").data() == data
+                });
+                if let Some(i) = equal {
+                    self.link(old_children[i], new_child);
+                }
+            }
+            old_children.retain(|child_id| !self.old_to_new.contains_key(child_id));
+            new_children.retain(|child_id| !self.new_to_old.contains_key(child_id));
+            for (old_child, new_child) in old_children.into_iter().zip(new_children) {
+                self.link(old_child, new_child);
+            }
+        }
+    }
+}
+
+/// Derives an edit script from a matching, following Chawathe et al., "Change Detection in
+/// Hierarchically Structured Information".  The `Edit`s are replayed on a data-less copy of the
+/// old `Tree` as they're generated.
+struct Script<'m, 'a, T> {
+    matcher: &'m Matcher<'a, T>,
+    work: Tree<()>,
+    work_to_patch: IdMap,
+    new_to_work: IdMap,
+    work_to_new: IdMap,
+    in_order: HashSet<NodeId>,
+    edits: Vec<Edit<T>>,
+}
+
+impl<'m, 'a, T: Clone + Eq> Script<'m, 'a, T> {
+    fn new(matcher: &'m Matcher<'a, T>) -> Script<'m, 'a, T> {
+        let (work, old_to_work) = matcher.old.map(|_| ());
+        let work_to_patch = old_to_work.iter().map(|(&o, &w)| (w, o)).collect();
+        let new_to_work: IdMap = matcher
+            .old_to_new
+            .iter()
+            .map(|(old_id, &new_id)| (new_id, old_to_work[old_id]))
+            .collect();
+        let work_to_new = new_to_work.iter().map(|(&n, &w)| (w, n)).collect();
+        Script {
+            matcher,
+            work,
+            work_to_patch,
+            new_to_work,
+            work_to_new,
+            in_order: HashSet::new(),
+            edits: Vec::new(),
+        }
+    }
+
+    fn generate(mut self) -> Vec<Edit<T>> {
+        let new = self.matcher.new;
+        for new_id in rooted_ids(new, Order::Level) {
+            let data = new.get(new_id).expect("rooted node").data();
+            let parent = new.get_node_relatives(new_id).parent;
+            let work_id = match (self.new_to_work.get(&new_id).copied(), parent) {
+                (None, None) => {
+                    let work_id = self.work.set_root(());
+                    self.edits.push(Edit::Insert {
+                        node_id: new_id,
+                        parent_id: None,
+                        index: 0,
+                        data: data.clone(),
+                    });
+                    self.pair(work_id, new_id);
+                    work_id
+                }
+                (None, Some(new_parent)) => {
+                    let work_parent = self.new_to_work[&new_parent];
+                    let index = self.find_position(new_id, None);
+                    let position = self
+                        .work
+                        .check_position(work_parent, None, Position::Index(index))
+                        .expect("position among existing children");
+                    let work_id = self.work.core_tree.insert(());
+                    self.work.link_at(work_id, work_parent, position);
+                    self.edits.push(Edit::Insert {
+                        node_id: new_id,
+                        parent_id: Some(self.work_to_patch[&work_parent]),
+                        index,
+                        data: data.clone(),
+                    });
+                    self.pair(work_id, new_id);
+                    self.mark_in_order(work_id, new_id);
+                    work_id
+                }
+                (Some(work_id), parent) => {
+                    let old_id = self.work_to_patch[&work_id];
+                    if self.matcher.old.get(old_id).expect("matched node").data() != data {
+                        self.edits.push(Edit::Update {
+                            node_id: old_id,
+                            data: data.clone(),
+                        });
+                    }
+                    if let Some(new_parent) = parent {
+                        let work_parent = self.new_to_work[&new_parent];
+                        if self.work.get_node_relatives(work_id).parent != Some(work_parent) {
+                            let index = self.find_position(new_id, Some(work_id));
+                            self.move_work(work_id, work_parent, index);
+                            self.edits.push(Edit::Move {
+                                node_id: old_id,
+                                parent_id: self.work_to_patch[&work_parent],
+                                index,
+                            });
+                            self.mark_in_order(work_id, new_id);
+                        }
+                    }
+                    work_id
+                }
+            };
+            self.align_children(work_id, new_id);
+        }
+
+        for work_id in rooted_ids(&self.work, Order::Pre) {
+            if self.work.get(work_id).is_some() && !self.work_to_new.contains_key(&work_id) {
+                self.edits.push(Edit::Delete {
+                    node_id: self.work_to_patch[&work_id],
+                });
+                self.work.remove(work_id, RemoveBehavior::DropChildren);
+            }
+        }
+        debug_assert!(self.work == new.map(|_| ()).0);
+        self.edits
+    }
+
+    fn pair(&mut self, work_id: NodeId, new_id: NodeId) {
+        self.work_to_patch.insert(work_id, new_id);
+        self.new_to_work.insert(new_id, work_id);
+        self.work_to_new.insert(work_id, new_id);
+    }
+
+    fn mark_in_order(&mut self, work_id: NodeId, new_id: NodeId) {
+        self.in_order.insert(work_id);
+        self.in_order.insert(new_id);
+    }
+
+    fn move_work(&mut self, work_id: NodeId, work_parent: NodeId, index: usize) {
+        self.work
+            .get_mut(work_id)
+            .expect("working node")
+            .move_to(work_parent, Position::Index(index))
+            .expect("moving a matched node into place");
+    }
+
+    /// Returns the index among the children of the partner of its parent that `new_id`'s partner
+    /// must take: right after the partner of its closest left sibling that is in order.  Indexes
+    /// leave out `work_id`, the partner being moved, if any.
+    fn find_position(&self, new_id: NodeId, work_id: Option<NodeId>) -> usize {
+        let new = self.matcher.new;
+        let mut sibling = new.get_node_relatives(new_id).prev_sibling;
+        while let Some(sibling_id) = sibling {
+            if self.in_order.contains(&sibling_id) {
+                break;
+            }
+            sibling = new.get_node_relatives(sibling_id).prev_sibling;
+        }
+        let Some(sibling_id) = sibling else {
+            return 0;
+        };
+        let work_sibling = self.new_to_work[&sibling_id];
+        let work_parent = self
+            .work
+            .get_node_relatives(work_sibling)
+            .parent
+            .expect("sibling has a parent");
+        child_ids(&self.work, work_parent)
+            .into_iter()
+            .filter(|&child_id| Some(child_id) != work_id)
+            .position(|child_id| child_id == work_sibling)
+            .expect("sibling is a child")
+            + 1
+    }
+
+    /// Puts the children of `work_id` that match children of `new_id` in the same order, moving
+    /// as few of them as possible.
+    fn align_children(&mut self, work_id: NodeId, new_id: NodeId) {
+        let new = self.matcher.new;
+        let work_children = child_ids(&self.work, work_id);
+        let new_children = child_ids(new, new_id);
+        for child_id in work_children.iter().chain(&new_children) {
+            self.in_order.remove(child_id);
+        }
+        let kept: Vec<NodeId> = work_children
+            .into_iter()
+            .filter(|work_child| {
+                self.work_to_new.get(work_child).is_some_and(|new_child| {
+                    new.get_node_relatives(*new_child).parent == Some(new_id)
+                })
+            })
+            .collect();
+        let matched: Vec<NodeId> = new_children
+            .into_iter()
+            .filter(|new_child| {
+                self.new_to_work.get(new_child).is_some_and(|work_child| {
+                    self.work.get_node_relatives(*work_child).parent == Some(work_id)
+                })
+            })
+            .collect();
+        // `kept` and `matched` hold the two sides of the same pairs, so the longest common
+        // subsequence of the two is the longest run of `kept` that is increasing in `matched`
+        let new_index: HashMap<NodeId, usize> = matched
+            .iter()
+            .enumerate()
+            .map(|(index, &new_child)| (new_child, index))
+            .collect();
+        let positions: Vec<usize> = kept
+            .iter()
+            .map(|work_child| new_index[&self.work_to_new[work_child]])
+            .collect();
+        for i in longest_increasing(&positions) {
+            self.mark_in_order(kept[i], matched[positions[i]]);
+        }
+        for new_child in matched {
+            let work_child = self.new_to_work[&new_child];
+            if !self.in_order.contains(&work_child) {
+                let index = self.find_position(new_child, Some(work_child));
+                self.move_work(work_child, work_id, index);
+                self.edits.push(Edit::Reorder {
+                    node_id: self.work_to_patch[&work_child],
+                    index,
+                });
+                self.mark_in_order(work_child, new_child);
+            }
+        }
+    }
+}
+
+#[cfg_attr(tarpaulin, skip)]
+#[cfg(test)]
+mod diff_tests {
+    use super::*;
+    use crate::test_support::{Rng, mutate, random_tree};
+    use crate::tree;
+    use crate::tree::TreeBuilder;
+
+    fn round_trip(mut old: Tree<i32>, new: &Tree<i32>) -> usize {
+        let edits = old.diff(new).into_edits();
+        let len = edits.len();
+        old.apply_patch(edits).unwrap();
+        assert_eq!(&old, new);
+        len
+    }
+
+    #[test]
+    fn identical_trees() {
+        let old = tree!(0 => [1 => [2, 3], 4]);
+        let new = tree!(0 => [1 => [2, 3], 4]);
+        let diff = old.diff(&new);
+        assert!(diff.is_empty());
+        assert_eq!(diff.matching().len(), 5);
+        for (old_id, new_id) in diff.matching() {
+            assert_eq!(
+                old.get(*old_id).unwrap().data(),
+                new.get(*new_id).unwrap().data()
+            );
+        }
+    }
+
+    #[test]
+    fn empty_trees() {
+        let empty: Tree<i32> = Tree::new();
+        let tree = tree!(0 => [1 => [2]]);
+
+        assert!(empty.diff(&Tree::new()).is_empty());
+
+        let diff = tree.diff(&empty);
+        assert_eq!(
+            diff.edits(),
+            &[Edit::Delete {
+                node_id: tree.root_id().unwrap()
+            }]
+        );
+        let mut s = String::new();
+        diff.write_formatted(&mut s).unwrap();
+        assert_eq!(s, "- 0\n- └── 1\n-     └── 2\n");
+
+        let diff = empty.diff(&tree);
+        assert_eq!(diff.len(), 3);
+        let mut patched = Tree::new();
+        let inserted = patched.apply_patch(diff.into_edits()).unwrap();
+        assert_eq!(patched, tree);
+        assert_eq!(inserted.len(), 3);
+    }
+
+    #[test]
+    fn update_and_reorder() {
+        tree!(let old = root @ 0 => [one @ 1, 2, three @ 3]);
+        let new = tree!(10 => [3, 1, 2]);
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.edits(),
+            &[
+                Edit::Update {
+                    node_id: root,
+                    data: 10
+                },
+                Edit::Reorder {
+                    node_id: three,
+                    index: 0
+                },
+            ]
+        );
+        assert!(diff.matching().contains_key(&one));
+
+        let mut s = String::new();
+        diff.write_formatted(&mut s).unwrap();
+        assert_eq!(s, "~ 0 -> 10\n> ├── 3\n  ├── 1\n  └── 2\n");
+    }
+
+    #[test]
+    fn delete_and_insert_subtrees() {
+        tree!(let old = 0 => [1, two @ 2 => [3 => [4]], 5]);
+        let new = tree!(0 => [1, 5 => [6 => [7]]]);
+        let diff = old.diff(&new);
+        assert_eq!(diff.edits().len(), 3);
+        assert_eq!(diff.edits()[2], Edit::Delete { node_id: two });
+
+        let mut s = String::new();
+        diff.write_formatted(&mut s).unwrap();
+        assert_eq!(
+            s,
+            "  \
+  0
+  ├── 1
+- ├── 2
+- │   └── 3
+- │       └── 4
+  └── 5
++     └── 6
++         └── 7
+"
+        );
+        round_trip(old, &new);
+    }
+
+    #[test]
+    fn moved_subtree_keeps_its_nodes() {
+        tree!(let old = 0 => [1 => [a @ 10 => [11, 12]], 2]);
+        let new = tree!(0 => [1, 2 => [10 => [11, 12, 13]]]);
+        let diff = old.diff(&new);
+        let new_ten = new.find(&10).unwrap()[0];
+        assert_eq!(diff.matching()[&a], new_ten);
+        assert_eq!(round_trip(old, &new), 2);
+    }
+
+    #[test]
+    fn multi_line_data() {
+        let old = TreeBuilder::new().with_root("a\nb").build();
+        let mut new = TreeBuilder::new().with_root("a\nb").build();
+        new.root_mut().unwrap().append("c\nd");
+        new.root_mut().unwrap().append("e");
+        let mut s = String::new();
+        old.diff(&new).write_formatted(&mut s).unwrap();
+        assert_eq!(s, "  a\n  b\n+ ├── c\n+ │   d\n+ └── e\n");
+    }
+
+    #[test]
+    fn apply_patch_errors() {
+        tree!(let mut tree = root @ 0 => [one @ 1]);
+        let other = tree!(0);
+        let other_id = other.root_id().unwrap();
+
+        let missing = Edit::Delete { node_id: other_id };
+        assert_eq!(
+            tree.apply_patch(vec![missing]),
+            Err(PatchError::NodeNotFound(other_id))
+        );
+        let root_insert = Edit::Insert {
+            node_id: other_id,
+            parent_id: None,
+            index: 0,
+            data: 5,
+        };
+        assert_eq!(
+            tree.apply_patch(vec![root_insert]),
+            Err(PatchError::RootExists)
+        );
+        let past_end = Edit::Insert {
+            node_id: other_id,
+            parent_id: Some(root),
+            index: 2,
+            data: 5,
+        };
+        assert_eq!(
+            tree.apply_patch(vec![past_end]),
+            Err(PatchError::Move(MoveError::IndexOutOfBounds(2)))
+        );
+        let reorder_root = Edit::Reorder {
+            node_id: root,
+            index: 0,
+        };
+        assert_eq!(
+            tree.apply_patch(vec![reorder_root]),
+            Err(PatchError::Move(MoveError::RootNotMovable))
+        );
+        let move_root = Edit::Move {
+            node_id: root,
+            parent_id: one,
+            index: 0,
+        };
+        assert_eq!(
+            tree.apply_patch(vec![move_root]),
+            Err(PatchError::Move(MoveError::RootNotMovable))
+        );
+        assert_eq!(tree, tree!(0 => [1]));
+    }
+
+    #[test]
+    fn longest_increasing_runs() {
+        assert_eq!(longest_increasing(&[]), Vec::<usize>::new());
+        assert_eq!(longest_increasing(&[0, 1, 2]), vec![0, 1, 2]);
+        assert_eq!(longest_increasing(&[2, 1, 0]).len(), 1);
+        assert_eq!(longest_increasing(&[3, 0, 1, 4, 2]), vec![1, 2, 4]);
+        assert_eq!(longest_increasing(&[4, 0, 5, 1, 2, 3]), vec![1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn wide_node() {
+        // a full table for the common subsequence of the children would hold 400 million entries
+        let width = 20_000;
+        let mut old = TreeBuilder::new().with_root(-1).build();
+        old.root_mut().unwrap().extend(0..width);
+        let mut new = TreeBuilder::new().with_root(-1).build();
+        new.root_mut()
+            .unwrap()
+            .extend(std::iter::once(width - 1).chain(0..width - 1));
+
+        let diff = old.diff(&new);
+        let last_id = old.root().unwrap().last_child().unwrap().node_id();
+        assert_eq!(
+            diff.edits(),
+            &[Edit::Reorder {
+                node_id: last_id,
+                index: 0
+            }]
+        );
+        assert_eq!(round_trip(old, &new), 1);
+    }
+
+    #[test]
+    fn random_round_trips() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..200 {
+            let size = 1 + rng.below(30);
+            let old = random_tree(&mut rng, size);
+            let mut new = old.clone();
+            let steps = 1 + rng.below(8);
+            mutate(&mut rng, &mut new, steps);
+            round_trip(old.clone(), &new);
+
+            let size = 1 + rng.below(30);
+            let unrelated = random_tree(&mut rng, size);
+            round_trip(old, &unrelated);
+        }
+    }
+}
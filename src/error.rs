@@ -0,0 +1,306 @@
+use std::fmt;
+
+use crate::NodeId;
+use crate::tree::Tree;
+
+///
+/// The ways in which moving a `Node` to a new place in the `Tree` can fail.  The `Tree` is left
+/// untouched when any of these is returned.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    ///
+    /// The new parent does not exist in the `Tree`.
+    ///
+    ParentNotFound(NodeId),
+
+    ///
+    /// The sibling given in the `Position` is not a child of the new parent.
+    ///
+    NotAChild(NodeId),
+
+    ///
+    /// The index given in the `Position` is past the end of the new parent's children.
+    ///
+    IndexOutOfBounds(usize),
+
+    ///
+    /// The new parent is the moved `Node` itself or one of its descendants.
+    ///
+    WouldCreateCycle,
+
+    ///
+    /// The root of the `Tree` cannot be moved under another `Node`.
+    ///
+    RootNotMovable,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::ParentNotFound(id) => write!(f, "parent {id:?} does not exist"),
+            MoveError::NotAChild(id) => write!(f, "{id:?} is not a child of the new parent"),
+            MoveError::IndexOutOfBounds(index) => {
+                write!(
+                    f,
+                    "index {index} is past the end of the new parent's children"
+                )
+            }
+            MoveError::WouldCreateCycle => {
+                write!(
+                    f,
+                    "cannot move a node under itself or one of its descendants"
+                )
+            }
+            MoveError::RootNotMovable => write!(f, "cannot move the root of the tree"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+///
+/// The error returned when `NodeMut::graft` can't attach a `Tree` where it was asked to.  The
+/// `Tree` is handed back untouched, and so is the `Tree` it was to be grafted into.
+///
+#[derive(Debug, PartialEq)]
+pub struct GraftError<T> {
+    ///
+    /// Why the `Tree` couldn't be attached.
+    ///
+    pub error: MoveError,
+
+    ///
+    /// The `Tree` that was to be grafted.
+    ///
+    pub tree: Tree<T>,
+}
+
+impl<T> fmt::Display for GraftError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot graft tree: {}", self.error)
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for GraftError<T> {}
+
+///
+/// The ways in which `Tree::try_remove` can fail.  The `Tree` is left untouched when any of these
+/// is returned.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RemoveError {
+    ///
+    /// The `Node` to remove does not exist in the `Tree`.
+    ///
+    NodeNotFound,
+
+    ///
+    /// `PromoteChildren` was asked to remove a root with more than one child, which would leave
+    /// the `Tree` with several roots.
+    ///
+    RootHasManyChildren,
+
+    ///
+    /// The `Node` given to `ReparentChildrenTo` does not exist in the `Tree`.
+    ///
+    ParentNotFound(NodeId),
+
+    ///
+    /// The `Node` given to `ReparentChildrenTo` is the removed `Node` itself or one of its
+    /// descendants.
+    ///
+    WouldCreateCycle,
+}
+
+impl fmt::Display for RemoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoveError::NodeNotFound => write!(f, "node to remove does not exist"),
+            RemoveError::RootHasManyChildren => {
+                write!(
+                    f,
+                    "cannot promote the children of a root with several children"
+                )
+            }
+            RemoveError::ParentNotFound(id) => write!(f, "new parent {id:?} does not exist"),
+            RemoveError::WouldCreateCycle => {
+                write!(
+                    f,
+                    "cannot move children under the removed node or one of its descendants"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RemoveError {}
+
+///
+/// The ways in which building a `Tree` from a list of keyed `Node`s can fail.  Each variant names
+/// the key at fault.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BuildError<K> {
+    ///
+    /// No `Node` is without a parent, so there is nothing to use as the root.
+    ///
+    NoRoot,
+
+    ///
+    /// More than one `Node` is without a parent; this is the first one after the root.
+    ///
+    MultipleRoots(K),
+
+    ///
+    /// The same key is used for more than one `Node`.
+    ///
+    DuplicateKey(K),
+
+    ///
+    /// The parent of `node` is not the key of any `Node`.
+    ///
+    DanglingParent { node: K, parent: K },
+
+    ///
+    /// An edge points at a child which is not the key of any `Node`.
+    ///
+    UnknownChild(K),
+
+    ///
+    /// More than one edge points at this child.
+    ///
+    MultipleParents(K),
+
+    ///
+    /// This `Node` is part of a cycle of parent links, so it can't be reached from a root.  It is
+    /// the first `Node` of the list that is part of one.
+    ///
+    Cycle(K),
+}
+
+impl<K: fmt::Debug> fmt::Display for BuildError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::NoRoot => write!(f, "no node is without a parent"),
+            BuildError::MultipleRoots(key) => {
+                write!(f, "{key:?} is a second node without a parent")
+            }
+            BuildError::DuplicateKey(key) => write!(f, "{key:?} is used for more than one node"),
+            BuildError::DanglingParent { node, parent } => {
+                write!(f, "parent {parent:?} of {node:?} does not exist")
+            }
+            BuildError::UnknownChild(key) => write!(f, "child {key:?} does not exist"),
+            BuildError::MultipleParents(key) => write!(f, "{key:?} has more than one parent"),
+            BuildError::Cycle(key) => write!(f, "{key:?} is part of a cycle"),
+        }
+    }
+}
+
+impl<K: fmt::Debug> std::error::Error for BuildError<K> {}
+
+///
+/// An error met while parsing a `Tree` from text, along with where it was met.  Lines and columns
+/// start at 1; columns count characters.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError<E> {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind<E>,
+}
+
+impl<E> ParseError<E> {
+    pub(crate) fn new(line: usize, column: usize, kind: ParseErrorKind<E>) -> ParseError<E> {
+        ParseError { line, column, kind }
+    }
+}
+
+///
+/// The ways in which parsing a `Tree` from text can fail.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseErrorKind<E> {
+    ///
+    /// The line doesn't start with the `│`/`├──`/`└──` prefix of a `Node` that fits in the
+    /// `Tree` read so far, nor with the prefix of another line of the last `Node`'s data.
+    ///
+    InvalidPrefix,
+
+    ///
+    /// A `Node` follows a sibling that was drawn as the last one with `└──`.
+    ///
+    SiblingAfterLast,
+
+    ///
+    /// A `Node` drawn with `├──` is not followed by a sibling.
+    ///
+    MissingSibling,
+
+    ///
+    /// The line is indented less than the previous one, but not as much as any of its ancestors.
+    ///
+    InvalidIndent,
+
+    ///
+    /// The line is indented as much as the root, which would make it a second root.
+    ///
+    MultipleRoots,
+
+    ///
+    /// The data of a `Node` could not be parsed.
+    ///
+    Data(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ParseError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            ParseErrorKind::InvalidPrefix => write!(f, "unexpected prefix"),
+            ParseErrorKind::SiblingAfterLast => write!(f, "node follows the last of its siblings"),
+            ParseErrorKind::MissingSibling => write!(f, "expected another sibling"),
+            ParseErrorKind::InvalidIndent => {
+                write!(f, "indentation doesn't match any enclosing node")
+            }
+            ParseErrorKind::MultipleRoots => write!(f, "a tree can't have more than one root"),
+            ParseErrorKind::Data(error) => write!(f, "invalid node data: {error}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for ParseError<E> {}
+
+///
+/// The ways in which applying a list of `Edit`s to a `Tree` can fail.  The `Edit`s before the
+/// failing one stay applied.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PatchError {
+    ///
+    /// An `Edit` names a `Node` that is neither in the `Tree` nor inserted by an earlier `Edit`.
+    ///
+    NodeNotFound(NodeId),
+
+    ///
+    /// An `Insert` without a parent was applied to a `Tree` that already has a root.
+    ///
+    RootExists,
+
+    ///
+    /// An `Insert`, `Move` or `Reorder` couldn't put its `Node` where it asked to.
+    ///
+    Move(MoveError),
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::NodeNotFound(id) => write!(f, "node {id:?} does not exist"),
+            PatchError::RootExists => write!(f, "cannot insert a root into a tree that has one"),
+            PatchError::Move(error) => write!(f, "cannot place node: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
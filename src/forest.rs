@@ -0,0 +1,817 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{self, Display};
+
+use crate::IdMap;
+use crate::NodeId;
+use crate::behaviors::RemoveBehavior;
+use crate::error::RemoveError;
+use crate::node::{NodeMut, NodeRef};
+use crate::tree::{Tree, TreeBuilder};
+
+///
+/// An ordered list of trees whose `Node`s all live in the same storage, so that `NodeId`s are
+/// valid across every tree of the `Forest` and `Node`s can be moved from one tree to another.
+///
+/// Each root is handed out as a regular `NodeRef` or `NodeMut`.  Roots have no parent and aren't
+/// linked to each other as siblings, so `NodeRef::is_orphan` is true for them and the order of
+/// the roots is only changed through the methods of the `Forest`.  A root that is moved under
+/// another `Node` stops being a root; one that is wrapped in a new `Node` hands its place over to
+/// that `Node`.  A `Node` detached from one of the trees is an orphan and belongs to none of them.
+///
+#[derive(Debug)]
+pub struct Forest<T> {
+    tree: Tree<T>,
+    roots: Vec<NodeId>,
+}
+
+impl<T> Forest<T> {
+    ///
+    /// Creates a new, empty `Forest` with a capacity of 0.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let forest: Forest<i32> = Forest::new();
+    ///
+    /// assert_eq!(forest.root_count(), 0);
+    /// # assert_eq!(forest.capacity(), 0);
+    /// ```
+    ///
+    pub fn new() -> Forest<T> {
+        Forest::with_capacity(0)
+    }
+
+    ///
+    /// Creates a new, empty `Forest` with room for `capacity` `Node`s.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let forest: Forest<i32> = Forest::with_capacity(10);
+    ///
+    /// assert_eq!(forest.capacity(), 10);
+    /// ```
+    ///
+    pub fn with_capacity(capacity: usize) -> Forest<T> {
+        Forest {
+            tree: TreeBuilder::new().with_capacity(capacity).build(),
+            roots: Vec::new(),
+        }
+    }
+
+    ///
+    /// Returns the `Forest`'s current capacity.  Capacity is defined as the number of times new
+    /// `Node`s can be added to the `Forest` before it must allocate more memory.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let forest: Forest<i32> = Forest::new();
+    ///
+    /// assert_eq!(forest.capacity(), 0);
+    /// ```
+    ///
+    pub fn capacity(&self) -> usize {
+        self.tree.capacity()
+    }
+
+    ///
+    /// Returns an `Iterator` over the roots of the `Forest`, in order.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let mut forest = Forest::new();
+    /// forest.push_root(1);
+    /// forest.push_root(2);
+    ///
+    /// let roots: Vec<i32> = forest.roots().map(|root| *root.data()).collect();
+    /// assert_eq!(roots, vec![1, 2]);
+    /// ```
+    ///
+    pub fn roots(&self) -> impl Iterator<Item = NodeRef<'_, T>> {
+        self.root_ids()
+            .into_iter()
+            .map(|root_id| self.tree.get(root_id).expect("root must exist"))
+    }
+
+    ///
+    /// Returns the number of roots (i.e. of trees) in the `Forest`.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let mut forest = Forest::new();
+    /// forest.push_root(1).append(2);
+    /// forest.push_root(3);
+    ///
+    /// assert_eq!(forest.root_count(), 2);
+    /// ```
+    ///
+    pub fn root_count(&self) -> usize {
+        self.root_ids().len()
+    }
+
+    ///
+    /// Returns true if the `Node` identified by `node_id` exists and is one of the roots of the
+    /// `Forest`.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let mut forest = Forest::new();
+    /// let mut one = forest.push_root(1);
+    /// let two_id = one.append(2).node_id();
+    /// let one_id = one.node_id();
+    ///
+    /// assert!(forest.is_root(one_id));
+    /// assert!(!forest.is_root(two_id));
+    /// ```
+    ///
+    pub fn is_root(&self, node_id: NodeId) -> bool {
+        self.root_ids().contains(&node_id)
+    }
+
+    ///
+    /// Returns the `NodeRef` pointing to the `Node` that the given `NodeId` identifies, wherever
+    /// it is in the `Forest`.  Returns a `None`-value if the `Node` doesn't exist.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let mut forest = Forest::new();
+    /// let two_id = forest.push_root(1).append(2).node_id();
+    ///
+    /// let two = forest.get(two_id).unwrap();
+    /// assert_eq!(two.data(), &2);
+    /// assert_eq!(two.parent().unwrap().data(), &1);
+    /// ```
+    ///
+    pub fn get(&self, node_id: NodeId) -> Option<NodeRef<'_, T>> {
+        self.tree.get(node_id)
+    }
+
+    ///
+    /// Returns the `NodeMut` pointing to the `Node` that the given `NodeId` identifies, wherever
+    /// it is in the `Forest`.  Returns a `None`-value if the `Node` doesn't exist.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    /// use nary_tree::behaviors::Position;
+    ///
+    /// let mut forest = Forest::new();
+    /// let two_id = forest.push_root(1).append(2).node_id();
+    /// let three_id = forest.push_root(3).node_id();
+    ///
+    /// forest.get_mut(two_id).unwrap().move_to(three_id, Position::Last).unwrap();
+    /// assert_eq!(forest.get(two_id).unwrap().parent().unwrap().data(), &3);
+    /// ```
+    ///
+    pub fn get_mut(&mut self, node_id: NodeId) -> Option<NodeMut<'_, T>> {
+        // forget the roots that have been moved under other roots before they can be detached
+        self.roots = self.root_ids();
+        self.tree.get_mut(node_id)
+    }
+
+    ///
+    /// Adds a new root after the last root of the `Forest`.  Returns a `NodeMut` pointing to the
+    /// new root.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let mut forest = Forest::new();
+    /// forest.push_root(1).append(2);
+    /// forest.push_root(3);
+    ///
+    /// let mut s = String::new();
+    /// forest.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 1
+    /// └── 2
+    /// 3
+    /// ");
+    /// ```
+    ///
+    pub fn push_root(&mut self, data: T) -> NodeMut<'_, T> {
+        self.roots = self.root_ids();
+        let node_id = self.tree.insert_orphaned(data);
+        self.roots.push(node_id);
+        NodeMut::new(node_id, &mut self.tree)
+    }
+
+    ///
+    /// Adds a new root at position `index` among the roots of the `Forest`, shifting the roots
+    /// after it.  Returns `Some(NodeMut)` pointing to the new root, or `None` (dropping `data`) if
+    /// `index` is greater than the number of roots.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let mut forest = Forest::new();
+    /// forest.push_root(1);
+    /// forest.push_root(3);
+    ///
+    /// assert!(forest.insert_root_at(1, 2).is_some());
+    /// assert!(forest.insert_root_at(0, 0).is_some());
+    /// assert!(forest.insert_root_at(5, 5).is_none());
+    ///
+    /// let roots: Vec<i32> = forest.roots().map(|root| *root.data()).collect();
+    /// assert_eq!(roots, vec![0, 1, 2, 3]);
+    /// ```
+    ///
+    pub fn insert_root_at(&mut self, index: usize, data: T) -> Option<NodeMut<'_, T>> {
+        self.roots = self.root_ids();
+        if index > self.roots.len() {
+            return None;
+        }
+        let node_id = self.tree.insert_orphaned(data);
+        self.roots.insert(index, node_id);
+        Some(NodeMut::new(node_id, &mut self.tree))
+    }
+
+    ///
+    /// Moves the root at position `from` among the roots of the `Forest` to position `to`,
+    /// shifting the roots in between.  Returns false (leaving the `Forest` untouched) if either
+    /// position is out of bounds.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let mut forest = Forest::new();
+    /// forest.push_root(0);
+    /// forest.push_root(1);
+    /// forest.push_root(2);
+    ///
+    /// assert!(forest.move_root(0, 2));
+    /// assert!(!forest.move_root(0, 3));
+    ///
+    /// let roots: Vec<i32> = forest.roots().map(|root| *root.data()).collect();
+    /// assert_eq!(roots, vec![1, 2, 0]);
+    /// ```
+    ///
+    pub fn move_root(&mut self, from: usize, to: usize) -> bool {
+        self.roots = self.root_ids();
+        if from >= self.roots.len() || to >= self.roots.len() {
+            return false;
+        }
+        let root_id = self.roots.remove(from);
+        self.roots.insert(to, root_id);
+        true
+    }
+
+    ///
+    /// Removes the root identified by `node_id` and returns the data that it contained.  Returns
+    /// `RemoveError::NodeNotFound` if the `Node` doesn't exist or isn't a root.
+    ///
+    /// The children of the removed root are dealt with according to `behavior`, as in
+    /// `Tree::try_remove`, except that `PromoteChildren` always succeeds: the children become
+    /// roots themselves, in the removed root's place.  `ReparentChildrenTo` may name a `Node` of
+    /// any other tree.  Every other `Node` keeps its `NodeId`.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    /// use nary_tree::behaviors::RemoveBehavior::*;
+    ///
+    /// let mut forest = Forest::new();
+    /// forest.push_root(0);
+    /// let (one_id, three_id) = {
+    ///     let mut one = forest.push_root(1);
+    ///     one.append(2);
+    ///     let three_id = one.append(3).node_id();
+    ///     (one.node_id(), three_id)
+    /// };
+    /// forest.push_root(4);
+    ///
+    /// assert_eq!(forest.remove_root(one_id, PromoteChildren), Ok(1));
+    /// assert!(forest.is_root(three_id));
+    ///
+    /// let roots: Vec<i32> = forest.roots().map(|root| *root.data()).collect();
+    /// assert_eq!(roots, vec![0, 2, 3, 4]);
+    /// ```
+    ///
+    pub fn remove_root(
+        &mut self,
+        node_id: NodeId,
+        behavior: RemoveBehavior,
+    ) -> Result<T, RemoveError> {
+        self.roots = self.root_ids();
+        let index = self
+            .roots
+            .iter()
+            .position(|&root_id| root_id == node_id)
+            .ok_or(RemoveError::NodeNotFound)?;
+        let child_ids: Vec<NodeId> = self
+            .tree
+            .get(node_id)
+            .expect("root must exist")
+            .children()
+            .map(|child| child.node_id())
+            .collect();
+
+        // a root has no parent, so `PromoteChildren` leaves its children without one too
+        let data = self.tree.try_remove(node_id, behavior)?;
+        if behavior == RemoveBehavior::PromoteChildren {
+            self.roots.splice(index..=index, child_ids);
+        } else {
+            self.roots.remove(index);
+        }
+        Ok(data)
+    }
+
+    ///
+    /// Moves every `Node` of `tree` into the `Forest`, adding its root (if any) after the last
+    /// root of the `Forest`.  Orphans of `tree` stay orphaned.  Returns a map from the `NodeId`s
+    /// the `Node`s had in `tree` to their `NodeId`s in the `Forest`.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(1).build();
+    /// let two_id = tree.root_mut().unwrap().append(2).node_id();
+    ///
+    /// let mut forest = Forest::new();
+    /// forest.push_root(0);
+    /// let id_map = forest.push_tree(tree);
+    ///
+    /// assert_eq!(forest.get(id_map[&two_id]).unwrap().data(), &2);
+    ///
+    /// let roots: Vec<i32> = forest.roots().map(|root| *root.data()).collect();
+    /// assert_eq!(roots, vec![0, 1]);
+    /// ```
+    ///
+    pub fn push_tree(&mut self, tree: Tree<T>) -> IdMap {
+        self.roots = self.root_ids();
+        let Tree { root_id, core_tree } = tree;
+        let id_map = self.tree.absorb(core_tree);
+        if let Some(root_id) = root_id {
+            self.roots.push(id_map[&root_id]);
+        }
+        id_map
+    }
+
+    ///
+    /// Splits the `Forest` into one `Tree` per root, in order.  Orphaned `Node`s are dropped.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let mut forest = Forest::new();
+    /// forest.push_root(1).append(2);
+    /// forest.push_root(3);
+    ///
+    /// let trees = forest.into_trees();
+    /// assert_eq!(trees.len(), 2);
+    /// assert_eq!(trees[0].root().unwrap().first_child().unwrap().data(), &2);
+    /// assert_eq!(trees[1].root().unwrap().data(), &3);
+    /// ```
+    ///
+    pub fn into_trees(mut self) -> Vec<Tree<T>> {
+        self.root_ids()
+            .into_iter()
+            .map(|root_id| {
+                let (tree, _) = self.tree.split_off(root_id).expect("root must exist");
+                tree
+            })
+            .collect()
+    }
+
+    ///
+    /// Depth-first pre-order traversal of every tree of the `Forest`, one root after the other.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let mut forest = Forest::new();
+    /// forest.push_root(0).append(1);
+    /// let mut two = forest.push_root(2);
+    /// two.append(3).append(4);
+    /// two.append(5);
+    ///
+    /// let values: Vec<i32> = forest.traverse_pre_order().map(|node_ref| *node_ref.data())
+    ///     .collect();
+    /// assert_eq!(values, vec![0, 1, 2, 3, 4, 5]);
+    /// ```
+    ///
+    pub fn traverse_pre_order(&self) -> impl Iterator<Item = NodeRef<'_, T>> {
+        self.roots().flat_map(|root| root.traverse_pre_order())
+    }
+
+    ///
+    /// Depth-first post-order traversal of every tree of the `Forest`, one root after the other.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let mut forest = Forest::new();
+    /// forest.push_root(0).append(1);
+    /// let mut two = forest.push_root(2);
+    /// two.append(3).append(4);
+    /// two.append(5);
+    ///
+    /// let values: Vec<i32> = forest.traverse_post_order().map(|node_ref| *node_ref.data())
+    ///     .collect();
+    /// assert_eq!(values, vec![1, 0, 4, 3, 5, 2]);
+    /// ```
+    ///
+    pub fn traverse_post_order(&self) -> impl Iterator<Item = NodeRef<'_, T>> {
+        self.roots().flat_map(|root| root.traverse_post_order())
+    }
+
+    ///
+    /// Breadth-first traversal of the whole `Forest`: every root first, then every `Node` at
+    /// depth 1, and so on.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let mut forest = Forest::new();
+    /// forest.push_root(0).append(1);
+    /// let mut two = forest.push_root(2);
+    /// two.append(3).append(4);
+    /// two.append(5);
+    ///
+    /// let values: Vec<i32> = forest.traverse_level_order().map(|node_ref| *node_ref.data())
+    ///     .collect();
+    /// assert_eq!(values, vec![0, 2, 1, 3, 5, 4]);
+    /// ```
+    ///
+    pub fn traverse_level_order(&self) -> impl Iterator<Item = NodeRef<'_, T>> {
+        let mut queue: VecDeque<NodeRef<'_, T>> = self.roots().collect();
+        std::iter::from_fn(move || {
+            let node_ref = queue.pop_front()?;
+            queue.extend(node_ref.children());
+            Some(node_ref)
+        })
+    }
+
+    /// Returns the roots in order, catching up with what was done to them through `NodeMut`s: a
+    /// root that was removed or moved under another root is dropped, and one that was moved
+    /// under an orphan (as `NodeMut::wrap` does) is replaced by the top of that orphan's tree.
+    fn root_ids(&self) -> Vec<NodeId> {
+        let listed: HashSet<NodeId> = self.roots.iter().copied().collect();
+        let mut seen = HashSet::new();
+        self.roots
+            .iter()
+            .filter_map(|&root_id| {
+                let root = self.tree.get(root_id)?;
+                let top_id = root
+                    .ancestors()
+                    .last()
+                    .map_or(root_id, |ancestor| ancestor.node_id());
+                let keep = top_id == root_id || !listed.contains(&top_id);
+                (keep && seen.insert(top_id)).then_some(top_id)
+            })
+            .collect()
+    }
+}
+
+impl<T> Default for Forest<T> {
+    fn default() -> Self {
+        Forest::new()
+    }
+}
+
+impl<T> From<Tree<T>> for Forest<T> {
+    fn from(tree: Tree<T>) -> Self {
+        let mut tree = tree;
+        let roots = tree.root_id.take().into_iter().collect();
+        Forest { tree, roots }
+    }
+}
+
+impl<T> TryFrom<Forest<T>> for Tree<T> {
+    type Error = Forest<T>;
+
+    ///
+    /// Turns a `Forest` with at most one root back into a `Tree`, keeping every `NodeId` valid.
+    /// A `Forest` with more than one root is handed back as the `Err`-value.
+    ///
+    fn try_from(mut forest: Forest<T>) -> Result<Self, Self::Error> {
+        let root_ids = forest.root_ids();
+        if root_ids.len() > 1 {
+            return Err(forest);
+        }
+        forest.tree.root_id = root_ids.first().copied();
+        Ok(forest.tree)
+    }
+}
+
+impl<T: Display> Forest<T> {
+    ///
+    /// Writes every tree of the `Forest` one after the other, each the way
+    /// `Tree::write_formatted` does.
+    ///
+    /// ```
+    /// use nary_tree::forest::Forest;
+    ///
+    /// let mut forest = Forest::new();
+    /// let mut zero = forest.push_root(0);
+    /// zero.append(1).append(2);
+    /// zero.append(3);
+    /// forest.push_root(4).append(5);
+    ///
+    /// let mut s = String::new();
+    /// forest.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 0
+    /// ├── 1
+    /// │   └── 2
+    /// └── 3
+    /// 4
+    /// └── 5
+    /// ");
+    /// ```
+    ///
+    pub fn write_formatted<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        for root_id in self.root_ids() {
+            self.tree.write_formatted_from(root_id, w)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Display> Display for Forest<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_formatted(f)
+    }
+}
+
+#[cfg_attr(tarpaulin, skip)]
+#[cfg(test)]
+mod forest_tests {
+    use super::*;
+    use crate::behaviors::Position;
+    use crate::behaviors::RemoveBehavior::{
+        DropChildren, OrphanChildren, PromoteChildren, ReparentChildrenTo,
+    };
+
+    fn root_data(forest: &Forest<i32>) -> Vec<i32> {
+        forest.roots().map(|root| *root.data()).collect()
+    }
+
+    fn three_roots() -> (Forest<i32>, Vec<NodeId>) {
+        let mut forest = Forest::new();
+        let ids = (1..=3).map(|i| forest.push_root(i).node_id()).collect();
+        (forest, ids)
+    }
+
+    #[test]
+    fn roots_have_no_siblings() {
+        let (forest, ids) = three_roots();
+
+        let one = forest.get(ids[0]).unwrap();
+        assert!(one.is_orphan());
+        assert!(one.parent().is_none());
+        assert!(one.next_sibling().is_none());
+        assert!(forest.get(ids[1]).unwrap().prev_sibling().is_none());
+    }
+
+    #[test]
+    fn sibling_moves_leave_roots_alone() {
+        let (mut forest, ids) = three_roots();
+
+        assert!(!forest.get_mut(ids[0]).unwrap().swap_next_sibling());
+        assert!(!forest.get_mut(ids[1]).unwrap().swap_prev_sibling());
+        assert!(!forest.get_mut(ids[1]).unwrap().make_first_sibling());
+        assert!(!forest.get_mut(ids[1]).unwrap().make_last_sibling());
+        assert!(!forest.get_mut(ids[1]).unwrap().detach());
+        assert_eq!(root_data(&forest), vec![1, 2, 3]);
+        assert!(ids.iter().all(|&id| forest.is_root(id)));
+    }
+
+    #[test]
+    fn move_across_roots() {
+        let (mut forest, ids) = three_roots();
+        let four_id = forest.get_mut(ids[1]).unwrap().append(4).node_id();
+
+        forest
+            .get_mut(four_id)
+            .unwrap()
+            .move_to(ids[0], Position::Last)
+            .unwrap();
+        assert_eq!(
+            forest.get(four_id).unwrap().parent().unwrap().node_id(),
+            ids[0]
+        );
+        assert_eq!(forest.to_string(), "1\n└── 4\n2\n3\n");
+    }
+
+    #[test]
+    fn move_root_under_another_root() {
+        let (mut forest, ids) = three_roots();
+
+        forest
+            .get_mut(ids[0])
+            .unwrap()
+            .move_to(ids[2], Position::First)
+            .unwrap();
+        assert_eq!(root_data(&forest), vec![2, 3]);
+        assert!(!forest.is_root(ids[0]));
+        assert_eq!(forest.to_string(), "2\n3\n└── 1\n");
+
+        forest.get_mut(ids[0]).unwrap().detach();
+        assert!(forest.get(ids[0]).unwrap().is_orphan());
+        assert_eq!(root_data(&forest), vec![2, 3]);
+    }
+
+    #[test]
+    fn move_root() {
+        let (mut forest, ids) = three_roots();
+
+        assert!(forest.move_root(2, 0));
+        assert_eq!(root_data(&forest), vec![3, 1, 2]);
+        assert!(forest.move_root(1, 1));
+        assert_eq!(root_data(&forest), vec![3, 1, 2]);
+        assert!(!forest.move_root(3, 0));
+        assert!(!forest.move_root(0, 3));
+        assert_eq!(root_data(&forest), vec![3, 1, 2]);
+        assert!(ids.iter().all(|&id| forest.is_root(id)));
+    }
+
+    #[test]
+    fn remove_first_root() {
+        let (mut forest, ids) = three_roots();
+        let four_id = forest.get_mut(ids[0]).unwrap().append(4).node_id();
+
+        assert_eq!(forest.remove_root(ids[0], DropChildren), Ok(1));
+        assert_eq!(root_data(&forest), vec![2, 3]);
+        assert!(forest.get(ids[0]).is_none());
+        assert!(forest.get(four_id).is_none());
+        assert!(forest.is_root(ids[1]));
+    }
+
+    #[test]
+    fn remove_root_orphan_children() {
+        let mut forest = Forest::new();
+        forest.push_root(0);
+        let (one_id, two_id) = {
+            let mut one = forest.push_root(1);
+            let two_id = one.append(2).node_id();
+            (one.node_id(), two_id)
+        };
+
+        assert_eq!(forest.remove_root(one_id, OrphanChildren), Ok(1));
+        assert_eq!(root_data(&forest), vec![0]);
+        assert!(forest.get(two_id).unwrap().is_orphan());
+        assert!(!forest.is_root(two_id));
+        assert_eq!(forest.into_trees().len(), 1);
+    }
+
+    #[test]
+    fn remove_root_reparent_children() {
+        let mut forest = Forest::new();
+        let zero_id = forest.push_root(0).node_id();
+        let (one_id, two_id, three_id) = {
+            let mut one = forest.push_root(1);
+            let mut two = one.append(2);
+            let three_id = two.append(3).node_id();
+            let two_id = two.node_id();
+            (one.node_id(), two_id, three_id)
+        };
+
+        assert_eq!(
+            forest.remove_root(one_id, ReparentChildrenTo(zero_id)),
+            Ok(1)
+        );
+        assert_eq!(
+            forest.get(two_id).unwrap().parent().unwrap().node_id(),
+            zero_id
+        );
+        assert_eq!(
+            forest.get(three_id).unwrap().parent().unwrap().node_id(),
+            two_id
+        );
+        assert_eq!(forest.to_string(), "0\n└── 2\n    └── 3\n");
+    }
+
+    #[test]
+    fn remove_root_reparent_children_errors() {
+        let mut forest = Forest::new();
+        let zero_id = forest.push_root(0).node_id();
+        let one_id = forest.push_root(1).append(2).node_id();
+        let removed_id = forest.push_root(3).node_id();
+        forest.remove_root(removed_id, DropChildren).unwrap();
+
+        assert_eq!(
+            forest.remove_root(zero_id, ReparentChildrenTo(zero_id)),
+            Err(RemoveError::WouldCreateCycle)
+        );
+        assert_eq!(
+            forest.remove_root(zero_id, ReparentChildrenTo(removed_id)),
+            Err(RemoveError::ParentNotFound(removed_id))
+        );
+        assert_eq!(
+            forest.remove_root(zero_id, ReparentChildrenTo(one_id)),
+            Ok(0)
+        );
+        assert_eq!(forest.to_string(), "1\n└── 2\n");
+    }
+
+    #[test]
+    fn remove_root_not_a_root() {
+        let mut forest = Forest::new();
+        let two_id = forest.push_root(1).append(2).node_id();
+        let orphan_id = forest.push_root(3).node_id();
+        forest.get_mut(two_id).unwrap().detach();
+        forest.remove_root(orphan_id, DropChildren).unwrap();
+
+        assert_eq!(
+            forest.remove_root(two_id, DropChildren),
+            Err(RemoveError::NodeNotFound)
+        );
+        assert_eq!(
+            forest.remove_root(orphan_id, DropChildren),
+            Err(RemoveError::NodeNotFound)
+        );
+        assert!(forest.get(two_id).is_some());
+    }
+
+    #[test]
+    fn promote_children_of_first_root() {
+        let mut forest = Forest::new();
+        let (zero_id, one_id, two_id) = {
+            let mut zero = forest.push_root(0);
+            let one_id = zero.append(1).node_id();
+            let two_id = zero.append(2).node_id();
+            (zero.node_id(), one_id, two_id)
+        };
+        forest.push_root(3);
+
+        assert_eq!(forest.remove_root(zero_id, PromoteChildren), Ok(0));
+        assert_eq!(root_data(&forest), vec![1, 2, 3]);
+        assert!(forest.is_root(one_id));
+        assert!(forest.is_root(two_id));
+        assert!(forest.get(one_id).unwrap().next_sibling().is_none());
+    }
+
+    #[test]
+    fn wrap_root() {
+        let (mut forest, ids) = three_roots();
+
+        let twenty_id = forest.get_mut(ids[1]).unwrap().wrap(20).node_id();
+        assert_eq!(root_data(&forest), vec![1, 20, 3]);
+        assert!(forest.is_root(twenty_id));
+        assert!(!forest.is_root(ids[1]));
+        assert_eq!(forest.get(ids[1]).unwrap().parent().unwrap().data(), &20);
+    }
+
+    #[test]
+    fn insert_root_at() {
+        let (mut forest, _) = three_roots();
+
+        assert!(forest.insert_root_at(0, 0).is_some());
+        assert!(forest.insert_root_at(4, 4).is_some());
+        assert!(forest.insert_root_at(6, 6).is_none());
+        assert_eq!(root_data(&forest), vec![0, 1, 2, 3, 4]);
+        assert_eq!(forest.into_trees().len(), 5);
+    }
+
+    #[test]
+    fn tree_round_trip() {
+        let mut tree = TreeBuilder::new().with_root(1).build();
+        let two_id = tree.root_mut().unwrap().append(2).node_id();
+        let orphan_id = tree.insert_orphaned(5);
+
+        let forest = Forest::from(tree);
+        assert_eq!(root_data(&forest), vec![1]);
+
+        let tree = Tree::try_from(forest).unwrap();
+        assert_eq!(tree.root().unwrap().data(), &1);
+        assert_eq!(tree.get(two_id).unwrap().data(), &2);
+        assert_eq!(tree.get(orphan_id).unwrap().data(), &5);
+
+        let mut forest = Forest::new();
+        forest.push_root(0);
+        let id_map = forest.push_tree(tree);
+        assert_eq!(forest.get(id_map[&orphan_id]).unwrap().data(), &5);
+        assert!(!forest.is_root(id_map[&orphan_id]));
+        let forest = Tree::try_from(forest).unwrap_err();
+        assert_eq!(forest.root_count(), 2);
+
+        let trees = forest.into_trees();
+        assert_eq!(trees[1].root().unwrap().first_child().unwrap().data(), &2);
+    }
+
+    #[test]
+    fn capacity_counts_nodes() {
+        let mut forest = Forest::with_capacity(4);
+        forest.push_root(1).append(2);
+        forest.push_root(3);
+
+        assert_eq!(forest.capacity(), 4);
+    }
+
+    #[test]
+    fn empty_forest() {
+        let forest: Forest<i32> = Forest::new();
+        assert_eq!(forest.traverse_level_order().count(), 0);
+        assert!(forest.into_trees().is_empty());
+
+        let mut forest: Forest<i32> = Forest::new();
+        assert!(forest.insert_root_at(1, 1).is_none());
+        assert!(!forest.move_root(0, 0));
+        assert!(Tree::try_from(forest).unwrap().root().is_none());
+    }
+}
@@ -1,4 +1,6 @@
 use crate::NodeId;
+use crate::core_tree;
+use crate::core_tree::CoreTree;
 use crate::node::*;
 use crate::tree::Tree;
 
@@ -221,3 +223,176 @@ impl<'a, T> Iterator for LevelOrder<'a, T> {
         }
     }
 }
+
+/// Iterator over the orphaned `Node`s of a `Tree` that are the root of an orphaned sub-tree
+pub struct Orphans<'a, T> {
+    nodes: core_tree::Iter<'a, T>,
+    tree: &'a Tree<T>,
+}
+
+impl<'a, T> Orphans<'a, T> {
+    pub(crate) fn new(tree: &'a Tree<T>) -> Orphans<'a, T> {
+        let nodes = tree.core_tree.iter();
+        Orphans { nodes, tree }
+    }
+}
+
+impl<'a, T> Iterator for Orphans<'a, T> {
+    type Item = NodeRef<'a, T>;
+
+    fn next(&mut self) -> Option<NodeRef<'a, T>> {
+        let root_id = self.tree.root_id();
+        self.nodes
+            .find(|(node_id, node)| node.relatives.parent.is_none() && Some(*node_id) != root_id)
+            .map(|(node_id, _)| NodeRef::new(node_id, self.tree))
+    }
+}
+
+/// Iterator which removes the `Node`s of a sub-tree from a `Tree` as it yields their data.  The
+/// `Node`s that haven't been yielded yet are removed when it is dropped.
+pub struct DrainSubtree<'a, T> {
+    node_ids: std::vec::IntoIter<(usize, NodeId)>,
+    tree: &'a mut Tree<T>,
+}
+
+impl<'a, T> DrainSubtree<'a, T> {
+    pub(crate) fn new(
+        node_ids: Vec<(usize, NodeId)>,
+        tree: &'a mut Tree<T>,
+    ) -> DrainSubtree<'a, T> {
+        DrainSubtree {
+            node_ids: node_ids.into_iter(),
+            tree,
+        }
+    }
+
+    ///
+    /// Pairs the data of each drained `Node` with its depth below the `Node` the sub-tree was
+    /// drained from, which has a depth of 0.
+    ///
+    pub fn with_depth(self) -> DrainSubtreeWithDepth<'a, T> {
+        DrainSubtreeWithDepth { drain: self }
+    }
+
+    fn next_with_depth(&mut self) -> Option<(usize, T)> {
+        self.node_ids.next().map(|(depth, node_id)| {
+            let data = self
+                .tree
+                .core_tree
+                .remove(node_id)
+                .expect("drained node must exist");
+            (depth, data)
+        })
+    }
+}
+
+impl<T> Iterator for DrainSubtree<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.next_with_depth().map(|(_, data)| data)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.node_ids.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for DrainSubtree<'_, T> {}
+
+impl<T> Drop for DrainSubtree<'_, T> {
+    fn drop(&mut self) {
+        while self.next_with_depth().is_some() {}
+    }
+}
+
+/// Iterator which yields the data of a drained sub-tree along with the depth of each `Node`
+pub struct DrainSubtreeWithDepth<'a, T> {
+    drain: DrainSubtree<'a, T>,
+}
+
+impl<T> Iterator for DrainSubtreeWithDepth<'_, T> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<(usize, T)> {
+        self.drain.next_with_depth()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for DrainSubtreeWithDepth<'_, T> {}
+
+/// Iterator which consumes a `Tree` and yields the data of its `Node`s along with their depth
+pub struct IntoIter<T> {
+    records: std::vec::IntoIter<(usize, Option<usize>, NodeId)>,
+    core_tree: CoreTree<T>,
+}
+
+impl<T> IntoIter<T> {
+    pub(crate) fn new(
+        records: Vec<(usize, Option<usize>, NodeId)>,
+        core_tree: CoreTree<T>,
+    ) -> IntoIter<T> {
+        IntoIter {
+            records: records.into_iter(),
+            core_tree,
+        }
+    }
+
+    ///
+    /// Pairs the data of each `Node` with the index (in the order this `Iterator` yields them) of
+    /// its parent instead of its depth.  The root is paired with `None`.
+    ///
+    pub fn with_parents(self) -> IntoIterWithParents<T> {
+        IntoIterWithParents { iter: self }
+    }
+
+    fn next_record(&mut self) -> Option<(usize, Option<usize>, T)> {
+        self.records.next().map(|(depth, parent, node_id)| {
+            let data = self
+                .core_tree
+                .remove(node_id)
+                .expect("node of the tree must exist");
+            (depth, parent, data)
+        })
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<(usize, T)> {
+        self.next_record().map(|(depth, _, data)| (depth, data))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.records.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+/// Iterator which consumes a `Tree` and yields the data of its `Node`s along with the index of
+/// their parent
+pub struct IntoIterWithParents<T> {
+    iter: IntoIter<T>,
+}
+
+impl<T> Iterator for IntoIterWithParents<T> {
+    type Item = (Option<usize>, T);
+
+    fn next(&mut self) -> Option<(Option<usize>, T)> {
+        self.iter
+            .next_record()
+            .map(|(_, parent, data)| (parent, data))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIterWithParents<T> {}
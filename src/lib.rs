@@ -71,21 +71,50 @@
 
 extern crate slab as slab_tokio;
 
+mod macros;
+
 pub mod behaviors;
 mod core_tree;
+pub mod diff;
+pub mod error;
+pub mod forest;
 pub mod iter;
+pub mod merge;
 pub mod node;
+mod parse;
 mod slab;
 pub mod tree;
 
+#[cfg(test)]
+mod test_support;
+
+pub use crate::behaviors::Order;
+pub use crate::behaviors::Position;
 pub use crate::behaviors::RemoveBehavior;
+pub use crate::diff::Diff;
+pub use crate::diff::Edit;
+pub use crate::error::BuildError;
+pub use crate::error::GraftError;
+pub use crate::error::MoveError;
+pub use crate::error::ParseError;
+pub use crate::error::ParseErrorKind;
+pub use crate::error::PatchError;
+pub use crate::error::RemoveError;
+pub use crate::forest::Forest;
 pub use crate::iter::Ancestors;
+pub use crate::iter::DrainSubtree;
+pub use crate::iter::IntoIter;
 pub use crate::iter::NextSiblings;
+pub use crate::iter::Orphans;
+pub use crate::merge::Conflict;
+pub use crate::merge::ConflictKind;
+pub use crate::merge::Resolution;
 pub use crate::node::NodeMut;
 pub use crate::node::NodeRef;
 pub use crate::tree::Tree;
 pub use crate::tree::TreeBuilder;
 use snowflake::ProcessUniqueId;
+use std::collections::HashMap;
 
 ///
 /// An identifier used to differentiate between Nodes and tie
@@ -96,3 +125,15 @@ pub struct NodeId {
     tree_id: ProcessUniqueId,
     index: slab::Index,
 }
+
+///
+/// Maps the `NodeId`s a set of `Node`s had before they were moved or copied to another `Tree`
+/// onto the `NodeId`s they have now.
+///
+pub type IdMap = HashMap<NodeId, NodeId>;
+
+///
+/// Maps the keys a set of `Node`s was described with onto the `NodeId`s they were given in the
+/// `Tree` built from them.
+///
+pub type KeyMap<K> = HashMap<K, NodeId>;
@@ -0,0 +1,200 @@
+///
+/// Builds a `Tree` from a literal description of its `Node`s.
+///
+/// Each `Node` is written as its data, optionally followed by `=>` and a bracketed list of its
+/// children, so `0 => [1, 2 => [3]]` is a root holding `0` with the children `1` and `2`, the
+/// latter having a single child `3`.  The data can be any expression.
+///
+/// ```
+/// use nary_tree::tree;
+///
+/// let tree = tree!(0 => [1 => [2 => [3, 4]], 5 => [6 => [7], 8]]);
+///
+/// let mut s = String::new();
+/// tree.write_formatted(&mut s).unwrap();
+/// assert_eq!(&s, "\
+/// 0
+/// ├── 1
+/// │   └── 2
+/// │       ├── 3
+/// │       └── 4
+/// └── 5
+///     ├── 6
+///     │   └── 7
+///     └── 8
+/// ");
+/// ```
+///
+/// Written as a `let` statement, the macro also binds the `NodeId` of every `Node` whose data is
+/// prefixed with `name @`, so that the `Node`s can be looked up afterwards.
+///
+/// ```
+/// use nary_tree::tree;
+///
+/// tree!(let mut tree = "a" => [b @ "b" => ["c"], d @ "d"]);
+///
+/// assert_eq!(tree.get(b).unwrap().first_child().unwrap().data(), &"c");
+/// tree.get_mut(d).unwrap().append("e");
+/// assert_eq!(tree.get(d).unwrap().first_child().unwrap().data(), &"e");
+/// ```
+///
+/// Only the `let` form can bind names; in the expression form they are rejected.
+///
+/// ```compile_fail
+/// use nary_tree::tree;
+///
+/// let tree = tree!("a" => [b @ "b"]);
+/// ```
+///
+/// The `Tree` is built with a `TreeBuilder` holding the root and sized for every `Node`, and the
+/// other `Node`s are then appended in order.  Every `Node` costs a few levels of macro recursion,
+/// so very large literals may need a higher `recursion_limit`.
+///
+#[macro_export]
+macro_rules! tree {
+    (@root named $tree:ident $name:ident @ $data:expr => [$($children:tt)*]) => {
+        $crate::tree!(@build $tree $data, [$($children)*]);
+        let $name = $tree.root_id().expect("root was just set");
+        $crate::tree!(@children named $tree $name [$($children)*]);
+    };
+    (@root named $tree:ident $name:ident @ $data:expr) => {
+        $crate::tree!(@build $tree $data, []);
+        let $name = $tree.root_id().expect("root was just set");
+    };
+    (@root anon $tree:ident $name:ident @ $($rest:tt)*) => {
+        compile_error!("`name @ data` bindings are only allowed in `tree!(let ...)`");
+    };
+    (@root $mode:ident $tree:ident $data:expr => [$($children:tt)*]) => {
+        $crate::tree!(@build $tree $data, [$($children)*]);
+        #[allow(unused_variables)]
+        let root_id = $tree.root_id().expect("root was just set");
+        $crate::tree!(@children $mode $tree root_id [$($children)*]);
+    };
+    (@root $mode:ident $tree:ident $data:expr) => {
+        $crate::tree!(@build $tree $data, []);
+    };
+
+    (@build $tree:ident $data:expr, [$($children:tt)*]) => {
+        #[allow(unused_mut)]
+        let mut $tree = $crate::tree::TreeBuilder::new()
+            .with_root($data)
+            .with_capacity(1 + $crate::tree!(@count [$($children)*]))
+            .build();
+    };
+
+    (@count []) => { 0usize };
+    (@count [$name:ident @ $data:expr => [$($children:tt)*] $(, $($rest:tt)*)?]) => {
+        1 + $crate::tree!(@count [$($children)*]) + $crate::tree!(@count [$($($rest)*)?])
+    };
+    (@count [$name:ident @ $data:expr $(, $($rest:tt)*)?]) => {
+        1 + $crate::tree!(@count [$($($rest)*)?])
+    };
+    (@count [$data:expr => [$($children:tt)*] $(, $($rest:tt)*)?]) => {
+        1 + $crate::tree!(@count [$($children)*]) + $crate::tree!(@count [$($($rest)*)?])
+    };
+    (@count [$data:expr $(, $($rest:tt)*)?]) => {
+        1 + $crate::tree!(@count [$($($rest)*)?])
+    };
+
+    (@children $mode:ident $tree:ident $parent:ident []) => {};
+    (@children anon $tree:ident $parent:ident [$name:ident @ $($rest:tt)*]) => {
+        compile_error!("`name @ data` bindings are only allowed in `tree!(let ...)`");
+    };
+    (@children named $tree:ident $parent:ident
+        [$name:ident @ $data:expr => [$($children:tt)*] $(, $($rest:tt)*)?]) => {
+        let $name = $crate::tree!(@append $tree $parent $data);
+        $crate::tree!(@children named $tree $name [$($children)*]);
+        $crate::tree!(@children named $tree $parent [$($($rest)*)?]);
+    };
+    (@children named $tree:ident $parent:ident [$name:ident @ $data:expr $(, $($rest:tt)*)?]) => {
+        let $name = $crate::tree!(@append $tree $parent $data);
+        $crate::tree!(@children named $tree $parent [$($($rest)*)?]);
+    };
+    (@children $mode:ident $tree:ident $parent:ident
+        [$data:expr => [$($children:tt)*] $(, $($rest:tt)*)?]) => {
+        #[allow(unused_variables)]
+        let node_id = $crate::tree!(@append $tree $parent $data);
+        $crate::tree!(@children $mode $tree node_id [$($children)*]);
+        $crate::tree!(@children $mode $tree $parent [$($($rest)*)?]);
+    };
+    (@children $mode:ident $tree:ident $parent:ident [$data:expr $(, $($rest:tt)*)?]) => {
+        $crate::tree!(@append $tree $parent $data);
+        $crate::tree!(@children $mode $tree $parent [$($($rest)*)?]);
+    };
+
+    (@append $tree:ident $parent:ident $data:expr) => {
+        $tree
+            .get_mut($parent)
+            .expect("parent was just inserted")
+            .append($data)
+            .node_id()
+    };
+
+    (let mut $name:ident = $($node:tt)+) => {
+        $crate::tree!(@root named tree $($node)+);
+        let mut $name = tree;
+    };
+    (let $name:ident = $($node:tt)+) => {
+        $crate::tree!(@root named tree $($node)+);
+        let $name = tree;
+    };
+    () => {
+        $crate::tree::TreeBuilder::new().build()
+    };
+    ($($node:tt)+) => {{
+        $crate::tree!(@root anon tree $($node)+);
+        tree
+    }};
+}
+
+#[cfg_attr(tarpaulin, skip)]
+#[cfg(test)]
+mod macros_tests {
+    use crate::tree::Tree;
+
+    #[test]
+    fn empty() {
+        let tree: Tree<i32> = tree!();
+        assert!(tree.root().is_none());
+    }
+
+    #[test]
+    fn root_only() {
+        let tree = tree!(1);
+        let root = tree.root().unwrap();
+        assert_eq!(root.data(), &1);
+        assert!(root.first_child().is_none());
+    }
+
+    #[test]
+    fn expressions_and_trailing_commas() {
+        let base = 10;
+        let tree = tree!(base => [base + 1, (base + 2) * 2 => [-1,],]);
+        let data: Vec<i32> = tree
+            .root()
+            .unwrap()
+            .traverse_pre_order()
+            .map(|node_ref| *node_ref.data())
+            .collect();
+        assert_eq!(data, vec![10, 11, 24, -1]);
+    }
+
+    #[test]
+    fn named_nodes() {
+        tree!(let tree = root @ 0 => [one @ 1 => [two @ 2], 3 => [four @ 4 => [5]]]);
+
+        assert_eq!(tree.root_id(), Some(root));
+        assert_eq!(tree.get(one).unwrap().parent().unwrap().node_id(), root);
+        assert_eq!(tree.get(two).unwrap().parent().unwrap().node_id(), one);
+        let four = tree.get(four).unwrap();
+        assert_eq!(four.parent().unwrap().data(), &3);
+        assert_eq!(four.first_child().unwrap().data(), &5);
+    }
+
+    #[test]
+    fn named_root_only() {
+        tree!(let mut tree = root @ "root");
+        tree.get_mut(root).unwrap().append("child");
+        assert_eq!(tree.root().unwrap().first_child().unwrap().data(), &"child");
+    }
+}
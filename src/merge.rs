@@ -0,0 +1,1052 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+
+use crate::IdMap;
+use crate::NodeId;
+use crate::behaviors::Order;
+use crate::tree::{Tree, TreeBuilder};
+
+///
+/// A change made by both sides of a three-way merge that `Tree::merge3` couldn't combine on its
+/// own.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict<T> {
+    ///
+    /// The `NodeId` of the `Node` in the base `Tree`, or `None` for an `AddAdd` conflict.
+    ///
+    pub node_id: Option<NodeId>,
+
+    ///
+    /// The position of the `Node` in the base `Tree`, as the index of each `Node` on the way down
+    /// from the root among its siblings.  The root has an empty path.
+    ///
+    pub path: Vec<usize>,
+
+    ///
+    /// What both sides changed about the `Node`, along with the data involved in an `Update`.
+    ///
+    pub kind: ConflictKind<T>,
+}
+
+///
+/// The kinds of `Conflict`s a three-way merge can run into.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConflictKind<T> {
+    ///
+    /// Both sides changed the data of the `Node`, to different values.
+    ///
+    Update { base: T, ours: T, theirs: T },
+
+    ///
+    /// Our side deleted the `Node`, while their side changed it, moved it or put `Node`s under it.
+    ///
+    DeletedByOurs,
+
+    ///
+    /// Their side deleted the `Node`, while our side changed it, moved it or put `Node`s under it.
+    ///
+    DeletedByTheirs,
+
+    ///
+    /// Both sides moved the `Node`, under different parents.
+    ///
+    Move,
+
+    ///
+    /// The moves made by both sides would make the `Node` its own ancestor.
+    ///
+    Cycle,
+
+    ///
+    /// Both sides reordered the children of the `Node`, differently.
+    ///
+    Order,
+
+    ///
+    /// The base `Tree` is empty and both sides built different `Tree`s.
+    ///
+    AddAdd,
+}
+
+///
+/// How to settle a `Conflict`, as returned by the resolver passed to `Tree::merge3`.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Resolution<T> {
+    ///
+    /// Keep the change made by our side.
+    ///
+    Ours,
+
+    ///
+    /// Keep the change made by their side.
+    ///
+    Theirs,
+
+    ///
+    /// Use this data for the `Node`.  Only settles an `Update` conflict.
+    ///
+    Data(T),
+}
+
+impl<T> fmt::Display for Conflict<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            ConflictKind::Update { .. } => "both sides changed the data",
+            ConflictKind::DeletedByOurs => "deleted by ours, changed by theirs",
+            ConflictKind::DeletedByTheirs => "deleted by theirs, changed by ours",
+            ConflictKind::Move => "both sides moved the node",
+            ConflictKind::Cycle => "moves by both sides form a cycle",
+            ConflictKind::Order => "both sides reordered the children",
+            ConflictKind::AddAdd => "both sides added a tree",
+        };
+        write!(f, "{what} at /")?;
+        let path: Vec<String> = self.path.iter().map(|index| index.to_string()).collect();
+        write!(f, "{}", path.join("/"))
+    }
+}
+
+impl<T: Clone + Eq + Hash> Tree<T> {
+    ///
+    /// Combines the changes `ours` and `theirs` each made to a copy of `base`: inserted, deleted,
+    /// moved and reordered `Node`s as well as updated data.  The changes of each side are found
+    /// with `diff`, so orphaned `Node`s are ignored.
+    ///
+    /// A change made by one side only is kept.  A `Node` deleted by one side stays deleted unless
+    /// the other side changed it, moved it or put `Node`s under it.  Sub-trees both sides added
+    /// under the same parent are kept once if they are equal.  When both sides changed the same
+    /// thing differently, `resolver` is asked how to settle the `Conflict`; if it returns `None`
+    /// (or a `Resolution` that doesn't fit), the `Conflict` is returned along with every other
+    /// unsettled one instead of a merged `Tree`.
+    ///
+    /// ```
+    /// use nary_tree::merge::{ConflictKind, Resolution};
+    /// use nary_tree::tree;
+    /// use nary_tree::tree::Tree;
+    ///
+    /// let base = tree!("config" => ["port" => ["80"], "host" => ["localhost"]]);
+    /// let ours = tree!("config" => ["port" => ["8080"], "host" => ["localhost"]]);
+    /// let theirs = tree!("config" => ["host" => ["example.com"], "port" => ["80"], "tls"]);
+    ///
+    /// let merged = Tree::merge3(&base, &ours, &theirs, |_| None).unwrap();
+    /// assert_eq!(merged, tree!("config" => ["host" => ["example.com"], "port" => ["8080"], "tls"]));
+    ///
+    /// let theirs = tree!("config" => ["port" => ["443"], "host" => ["localhost"]]);
+    /// let conflicts = Tree::merge3(&base, &ours, &theirs, |_| None).unwrap_err();
+    /// assert_eq!(conflicts.len(), 1);
+    /// assert_eq!(conflicts[0].path, vec![0, 0]);
+    /// assert_eq!(
+    ///     conflicts[0].kind,
+    ///     ConflictKind::Update { base: "80", ours: "8080", theirs: "443" }
+    /// );
+    ///
+    /// let merged = Tree::merge3(&base, &ours, &theirs, |_| Some(Resolution::Theirs)).unwrap();
+    /// assert_eq!(merged, theirs);
+    /// ```
+    ///
+    pub fn merge3<R>(
+        base: &Tree<T>,
+        ours: &Tree<T>,
+        theirs: &Tree<T>,
+        mut resolver: R,
+    ) -> Result<Tree<T>, Vec<Conflict<T>>>
+    where
+        R: FnMut(&Conflict<T>) -> Option<Resolution<T>>,
+    {
+        if ours == theirs {
+            return Ok(ours.clone());
+        }
+        if base.root_id.is_none() {
+            return merge_added(ours, theirs, &mut resolver);
+        }
+        let mut merge = Merge::new(base, ours, theirs, resolver);
+        merge.match_added();
+        merge.merge_nodes();
+        merge.break_cycles();
+        merge.settle_deletions();
+        let children = merge.order_children();
+        if merge.conflicts.is_empty() {
+            Ok(merge.build(children))
+        } else {
+            Err(merge.conflicts)
+        }
+    }
+}
+
+/// Merges two `Tree`s built from an empty base.
+fn merge_added<T, R>(
+    ours: &Tree<T>,
+    theirs: &Tree<T>,
+    resolver: &mut R,
+) -> Result<Tree<T>, Vec<Conflict<T>>>
+where
+    T: Clone + Eq + Hash,
+    R: FnMut(&Conflict<T>) -> Option<Resolution<T>>,
+{
+    if theirs.root_id.is_none() {
+        return Ok(ours.clone());
+    }
+    if ours.root_id.is_none() {
+        return Ok(theirs.clone());
+    }
+    let conflict = Conflict {
+        node_id: None,
+        path: vec![],
+        kind: ConflictKind::AddAdd,
+    };
+    match resolver(&conflict) {
+        Some(Resolution::Ours) => Ok(ours.clone()),
+        Some(Resolution::Theirs) => Ok(theirs.clone()),
+        _ => Err(vec![conflict]),
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum Side {
+    Ours,
+    Theirs,
+}
+
+/// What becomes of a `Node` in the merged `Tree`.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Fate {
+    Live,
+    Dead,
+    /// Deleted by one side, but the other side changed it.
+    Contested,
+}
+
+/// The state of a three-way merge.  `Node`s are keyed by their `NodeId` in the base `Tree`, or in
+/// the `Tree` of the side that inserted them.
+struct Merge<'a, T, R> {
+    base: &'a Tree<T>,
+    ours: &'a Tree<T>,
+    theirs: &'a Tree<T>,
+    resolver: R,
+    base_to_ours: IdMap,
+    base_to_theirs: IdMap,
+    ours_to_base: IdMap,
+    theirs_to_base: IdMap,
+    /// Every key, the base `Node`s first, then those inserted by ours and then by theirs.
+    keys: Vec<NodeId>,
+    /// Sub-trees inserted by theirs that are equal to sub-trees inserted by ours.
+    duplicates: HashSet<NodeId>,
+    parents: HashMap<NodeId, Option<NodeId>>,
+    data: HashMap<NodeId, T>,
+    fates: HashMap<NodeId, Fate>,
+    deleted_by: HashMap<NodeId, Side>,
+    conflicts: Vec<Conflict<T>>,
+}
+
+impl<'a, T, R> Merge<'a, T, R>
+where
+    T: Clone + Eq + Hash,
+    R: FnMut(&Conflict<T>) -> Option<Resolution<T>>,
+{
+    fn new(
+        base: &'a Tree<T>,
+        ours: &'a Tree<T>,
+        theirs: &'a Tree<T>,
+        resolver: R,
+    ) -> Merge<'a, T, R> {
+        let base_to_ours = base.diff(ours).matching().clone();
+        let base_to_theirs = base.diff(theirs).matching().clone();
+        let ours_to_base: IdMap = base_to_ours.iter().map(|(&b, &o)| (o, b)).collect();
+        let theirs_to_base: IdMap = base_to_theirs.iter().map(|(&b, &t)| (t, b)).collect();
+        let mut keys = rooted_ids(base);
+        keys.extend(
+            rooted_ids(ours)
+                .into_iter()
+                .filter(|id| !ours_to_base.contains_key(id)),
+        );
+        keys.extend(
+            rooted_ids(theirs)
+                .into_iter()
+                .filter(|id| !theirs_to_base.contains_key(id)),
+        );
+        Merge {
+            base,
+            ours,
+            theirs,
+            resolver,
+            base_to_ours,
+            base_to_theirs,
+            ours_to_base,
+            theirs_to_base,
+            keys,
+            duplicates: HashSet::new(),
+            parents: HashMap::new(),
+            data: HashMap::new(),
+            fates: HashMap::new(),
+            deleted_by: HashMap::new(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    fn tree(&self, side: Side) -> &'a Tree<T> {
+        match side {
+            Side::Ours => self.ours,
+            Side::Theirs => self.theirs,
+        }
+    }
+
+    fn side_to_base(&self, side: Side) -> &IdMap {
+        match side {
+            Side::Ours => &self.ours_to_base,
+            Side::Theirs => &self.theirs_to_base,
+        }
+    }
+
+    fn base_to_side(&self, side: Side) -> &IdMap {
+        match side {
+            Side::Ours => &self.base_to_ours,
+            Side::Theirs => &self.base_to_theirs,
+        }
+    }
+
+    /// Returns the key of a `Node` of one side.
+    fn key(&self, side: Side, node_id: NodeId) -> NodeId {
+        self.side_to_base(side)
+            .get(&node_id)
+            .copied()
+            .unwrap_or(node_id)
+    }
+
+    /// Returns the `NodeId` the `Node` with the given key has on one side, if it is there.
+    fn side_id(&self, side: Side, key: NodeId) -> Option<NodeId> {
+        if let Some(&side_id) = self.base_to_side(side).get(&key) {
+            return Some(side_id);
+        }
+        let is_inserted = self.base.get(key).is_none()
+            && self.tree(side).get(key).is_some()
+            && !self.side_to_base(side).contains_key(&key);
+        is_inserted.then_some(key)
+    }
+
+    /// Returns the key of the parent a `Node` of one side has there.
+    fn side_parent(&self, side: Side, node_id: NodeId) -> Option<NodeId> {
+        self.tree(side)
+            .get_node_relatives(node_id)
+            .parent
+            .map(|parent_id| self.key(side, parent_id))
+    }
+
+    fn side_data(&self, side: Side, node_id: NodeId) -> &'a T {
+        self.tree(side).get(node_id).expect("rooted node").data()
+    }
+
+    fn base_data(&self, node_id: NodeId) -> &'a T {
+        self.base.get(node_id).expect("rooted node").data()
+    }
+
+    /// Returns the index of each `Node` on the way down from the root of the base `Tree` to the
+    /// given one among its siblings.
+    fn path(&self, node_id: NodeId) -> Vec<usize> {
+        let mut path = Vec::new();
+        let mut current = Some(node_id);
+        while let Some(id) = current {
+            let relatives = self.base.get_node_relatives(id);
+            if relatives.parent.is_none() {
+                break;
+            }
+            let mut index = 0;
+            let mut sibling = relatives.prev_sibling;
+            while let Some(sibling_id) = sibling {
+                index += 1;
+                sibling = self.base.get_node_relatives(sibling_id).prev_sibling;
+            }
+            path.push(index);
+            current = relatives.parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Asks the resolver about a `Conflict` on a base `Node`, and keeps the `Conflict` if it
+    /// can't be settled by one of the accepted `Resolution`s.
+    fn resolve(
+        &mut self,
+        node_id: NodeId,
+        kind: ConflictKind<T>,
+        accepts: impl Fn(&Resolution<T>) -> bool,
+    ) -> Option<Resolution<T>> {
+        let conflict = Conflict {
+            node_id: Some(node_id),
+            path: self.path(node_id),
+            kind,
+        };
+        match (self.resolver)(&conflict) {
+            Some(resolution) if accepts(&resolution) => Some(resolution),
+            _ => {
+                self.conflicts.push(conflict);
+                None
+            }
+        }
+    }
+
+    /// Finds the sub-trees both sides inserted under the same parent that are equal, so that
+    /// only our copy is kept.
+    fn match_added(&mut self) {
+        let tops = |side: Side| -> Vec<(NodeId, Option<NodeId>)> {
+            self.keys
+                .iter()
+                .copied()
+                .filter(|&key| self.base.get(key).is_none())
+                .filter(|&key| self.tree(side).get(key).is_some())
+                .filter(|&key| {
+                    let parent = self.tree(side).get_node_relatives(key).parent;
+                    parent.is_some_and(|parent| self.side_to_base(side).contains_key(&parent))
+                })
+                .filter(|&key| {
+                    self.tree(side)
+                        .subtree_ids(key, Order::Pre)
+                        .iter()
+                        .all(|(_, id)| !self.side_to_base(side).contains_key(id))
+                })
+                .map(|key| (key, self.side_parent(side, key)))
+                .collect()
+        };
+        let ours_tops = tops(Side::Ours);
+        let theirs_tops = tops(Side::Theirs);
+        let mut paired = HashSet::new();
+        for (theirs_top, parent) in theirs_tops {
+            let twin = ours_tops.iter().find(|(ours_top, ours_parent)| {
+                *ours_parent == parent
+                    && !paired.contains(ours_top)
+                    && self
+                        .ours
+                        .get(*ours_top)
+                        .expect("rooted node")
+                        .structurally_eq(&self.theirs.get(theirs_top).expect("rooted node"))
+            });
+            if let Some(&(ours_top, _)) = twin {
+                paired.insert(ours_top);
+                self.duplicates.extend(
+                    self.theirs
+                        .subtree_ids(theirs_top, Order::Pre)
+                        .into_iter()
+                        .map(|(_, id)| id),
+                );
+            }
+        }
+    }
+
+    /// Decides the parent and data of every `Node`, and which `Node`s deleted by one side were
+    /// changed by the other.
+    fn merge_nodes(&mut self) {
+        for key in self.keys.clone() {
+            if self.duplicates.contains(&key) {
+                continue;
+            }
+            if self.base.get(key).is_none() {
+                let side = if self.ours.get(key).is_some() {
+                    Side::Ours
+                } else {
+                    Side::Theirs
+                };
+                self.parents.insert(key, self.side_parent(side, key));
+                self.data.insert(key, self.side_data(side, key).clone());
+                self.fates.insert(key, Fate::Live);
+                continue;
+            }
+
+            let base_parent = self.base.get_node_relatives(key).parent;
+            let base_data = self.base_data(key);
+            let ours_id = self.base_to_ours.get(&key).copied();
+            let theirs_id = self.base_to_theirs.get(&key).copied();
+            match (ours_id, theirs_id) {
+                (Some(ours_id), Some(theirs_id)) => {
+                    let ours_parent = self.side_parent(Side::Ours, ours_id);
+                    let theirs_parent = self.side_parent(Side::Theirs, theirs_id);
+                    let parent = if ours_parent == theirs_parent || theirs_parent == base_parent {
+                        ours_parent
+                    } else if ours_parent == base_parent {
+                        theirs_parent
+                    } else {
+                        match self.resolve(key, ConflictKind::Move, |resolution| {
+                            !matches!(resolution, Resolution::Data(_))
+                        }) {
+                            Some(Resolution::Ours) => ours_parent,
+                            Some(Resolution::Theirs) => theirs_parent,
+                            _ => base_parent,
+                        }
+                    };
+
+                    let ours_data = self.side_data(Side::Ours, ours_id);
+                    let theirs_data = self.side_data(Side::Theirs, theirs_id);
+                    let data = if ours_data == theirs_data || theirs_data == base_data {
+                        ours_data.clone()
+                    } else if ours_data == base_data {
+                        theirs_data.clone()
+                    } else {
+                        let kind = ConflictKind::Update {
+                            base: base_data.clone(),
+                            ours: ours_data.clone(),
+                            theirs: theirs_data.clone(),
+                        };
+                        match self.resolve(key, kind, |_| true) {
+                            Some(Resolution::Ours) => ours_data.clone(),
+                            Some(Resolution::Theirs) => theirs_data.clone(),
+                            Some(Resolution::Data(data)) => data,
+                            None => base_data.clone(),
+                        }
+                    };
+                    self.parents.insert(key, parent);
+                    self.data.insert(key, data);
+                    self.fates.insert(key, Fate::Live);
+                }
+                (None, None) => {
+                    self.fates.insert(key, Fate::Dead);
+                }
+                (ours_id, theirs_id) => {
+                    let (side, side_id, deleted_by) = match (ours_id, theirs_id) {
+                        (Some(ours_id), _) => (Side::Ours, ours_id, Side::Theirs),
+                        (_, Some(theirs_id)) => (Side::Theirs, theirs_id, Side::Ours),
+                        (None, None) => unreachable!(),
+                    };
+                    let parent = self.side_parent(side, side_id);
+                    let data = self.side_data(side, side_id);
+                    let changed = parent != base_parent || data != base_data;
+                    self.parents.insert(key, parent);
+                    self.data.insert(key, data.clone());
+                    self.deleted_by.insert(key, deleted_by);
+                    let fate = if changed { Fate::Contested } else { Fate::Dead };
+                    self.fates.insert(key, fate);
+                }
+            }
+        }
+    }
+
+    /// Finds the `Node`s whose merged parents lead back to themselves, and asks the resolver to
+    /// pick the parents of one side for all of them.  Settling a cycle that way can still leave
+    /// one behind, in which case the resolver is asked again, but never twice for the same side.
+    fn break_cycles(&mut self) {
+        let mut unsettled = HashSet::new();
+        let mut tried = HashSet::new();
+        loop {
+            let cycles: Vec<Vec<NodeId>> = self
+                .find_cycles()
+                .into_iter()
+                .filter(|cycle| !cycle.iter().any(|key| unsettled.contains(key)))
+                .collect();
+            if cycles.is_empty() {
+                break;
+            }
+            for cycle in cycles {
+                // Nodes inserted by one side only hang under Nodes of that side, so every cycle
+                // holds a base Node, and the base Nodes come first among the keys
+                let first = *cycle
+                    .iter()
+                    .min_by_key(|&&key| self.keys.iter().position(|&k| k == key))
+                    .expect("cycles aren't empty");
+                let resolution =
+                    self.resolve(first, ConflictKind::Cycle, |resolution| match resolution {
+                        Resolution::Ours => !tried.contains(&(first, Side::Ours)),
+                        Resolution::Theirs => !tried.contains(&(first, Side::Theirs)),
+                        Resolution::Data(_) => false,
+                    });
+                let side = match resolution {
+                    Some(Resolution::Ours) => Side::Ours,
+                    Some(Resolution::Theirs) => Side::Theirs,
+                    _ => {
+                        unsettled.extend(cycle);
+                        continue;
+                    }
+                };
+                tried.insert((first, side));
+                for key in cycle {
+                    if let Some(side_id) = self.side_id(side, key) {
+                        let parent = self.side_parent(side, side_id);
+                        self.parents.insert(key, parent);
+                    }
+                }
+            }
+        }
+    }
+
+    fn find_cycles(&self) -> Vec<Vec<NodeId>> {
+        let mut done: HashSet<NodeId> = HashSet::new();
+        let mut cycles = Vec::new();
+        for &key in &self.keys {
+            let mut chain: Vec<NodeId> = Vec::new();
+            let mut current = Some(key);
+            while let Some(id) = current {
+                if done.contains(&id) {
+                    break;
+                }
+                if let Some(start) = chain.iter().position(|&c| c == id) {
+                    cycles.push(chain[start..].to_vec());
+                    break;
+                }
+                chain.push(id);
+                current = self.parents.get(&id).copied().flatten();
+            }
+            done.extend(chain);
+        }
+        cycles
+    }
+
+    /// Contests every `Node` deleted by one side that still has live or contested children, and
+    /// asks the resolver whether each contested `Node` stays deleted.  Ancestors are settled
+    /// first: a `Node` that stays deleted takes its whole sub-tree with it, so the resolver isn't
+    /// asked about the contested `Node`s under it.
+    fn settle_deletions(&mut self) {
+        for key in self.keys.clone() {
+            if self.fates.get(&key) == Some(&Fate::Dead) {
+                continue;
+            }
+            let mut current = self.parents.get(&key).copied().flatten();
+            while let Some(parent) = current {
+                let undecided = self.deleted_by.contains_key(&parent)
+                    && self.fates.get(&parent) == Some(&Fate::Dead);
+                if !undecided {
+                    break;
+                }
+                self.fates.insert(parent, Fate::Contested);
+                current = self.parents.get(&parent).copied().flatten();
+            }
+        }
+
+        let mut keys = self.keys.clone();
+        keys.sort_by_key(|&key| self.depth(key));
+        for key in keys {
+            let parent = self.parents.get(&key).copied().flatten();
+            if parent.is_some_and(|parent| self.fates.get(&parent) == Some(&Fate::Dead)) {
+                self.fates.insert(key, Fate::Dead);
+                continue;
+            }
+            if self.fates.get(&key) != Some(&Fate::Contested) {
+                continue;
+            }
+            let deleted_by = self.deleted_by[&key];
+            let kind = match deleted_by {
+                Side::Ours => ConflictKind::DeletedByOurs,
+                Side::Theirs => ConflictKind::DeletedByTheirs,
+            };
+            let fate = match self.resolve(key, kind, |resolution| {
+                !matches!(resolution, Resolution::Data(_))
+            }) {
+                Some(Resolution::Ours) if deleted_by == Side::Ours => Fate::Dead,
+                Some(Resolution::Theirs) if deleted_by == Side::Theirs => Fate::Dead,
+                _ => Fate::Live,
+            };
+            self.fates.insert(key, fate);
+        }
+    }
+
+    /// Returns the number of merged ancestors of a `Node`, stopping short of an unsettled cycle.
+    fn depth(&self, key: NodeId) -> usize {
+        let mut seen = HashSet::new();
+        let mut current = self.parents.get(&key).copied().flatten();
+        while let Some(parent) = current {
+            if parent == key || !seen.insert(parent) {
+                break;
+            }
+            current = self.parents.get(&parent).copied().flatten();
+        }
+        seen.len()
+    }
+
+    /// Returns the live children of every live `Node`, in order.  The order of the side that
+    /// reordered the common children is kept, and the children only the other side has are
+    /// slotted in after their closest left sibling there.
+    fn order_children(&mut self) -> HashMap<NodeId, Vec<NodeId>> {
+        let mut unordered: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        for &key in &self.keys {
+            if self.fates.get(&key) != Some(&Fate::Live) {
+                continue;
+            }
+            if let Some(Some(parent)) = self.parents.get(&key) {
+                unordered.entry(*parent).or_default().push(key);
+            }
+        }
+
+        let mut ordered = HashMap::with_capacity(unordered.len());
+        for parent in self.keys.clone() {
+            let Some(children) = unordered.remove(&parent) else {
+                continue;
+            };
+            if self.fates.get(&parent) != Some(&Fate::Live) {
+                continue;
+            }
+            let live: HashSet<NodeId> = children.iter().copied().collect();
+            let base_children = self.live_children(None, parent, &live);
+            let ours_children = self.live_children(Some(Side::Ours), parent, &live);
+            let theirs_children = self.live_children(Some(Side::Theirs), parent, &live);
+
+            let common: HashSet<NodeId> = base_children
+                .iter()
+                .copied()
+                .filter(|id| ours_children.contains(id) && theirs_children.contains(id))
+                .collect();
+            let in_common = |sequence: &[NodeId]| -> Vec<NodeId> {
+                sequence
+                    .iter()
+                    .copied()
+                    .filter(|id| common.contains(id))
+                    .collect()
+            };
+            let (base_order, ours_order, theirs_order) = (
+                in_common(&base_children),
+                in_common(&ours_children),
+                in_common(&theirs_children),
+            );
+            let ours_first = if ours_order == theirs_order || theirs_order == base_order {
+                true
+            } else if ours_order == base_order {
+                false
+            } else {
+                let resolution = self.resolve(parent, ConflictKind::Order, |resolution| {
+                    !matches!(resolution, Resolution::Data(_))
+                });
+                resolution != Some(Resolution::Theirs)
+            };
+            let (first, second) = if ours_first {
+                (ours_children, theirs_children)
+            } else {
+                (theirs_children, ours_children)
+            };
+
+            // the children only the second side has go after those only the first side has
+            let in_second: HashSet<NodeId> = second.iter().copied().collect();
+            let skip_first_only = |merged: &[NodeId], mut cursor: usize| {
+                while merged.get(cursor).is_some_and(|id| !in_second.contains(id)) {
+                    cursor += 1;
+                }
+                cursor
+            };
+            let mut merged = first;
+            let mut cursor = skip_first_only(&merged, 0);
+            for child in second {
+                match merged.iter().position(|&id| id == child) {
+                    Some(position) => cursor = skip_first_only(&merged, position + 1),
+                    None => {
+                        merged.insert(cursor, child);
+                        cursor += 1;
+                    }
+                }
+            }
+            for child in children {
+                if !merged.contains(&child) {
+                    merged.push(child);
+                }
+            }
+            ordered.insert(parent, merged);
+        }
+        ordered
+    }
+
+    /// Returns the keys of the children the `Node` with the given key has in the base `Tree` (for
+    /// `None`) or on one side, leaving out those that aren't `live`.
+    fn live_children(
+        &self,
+        side: Option<Side>,
+        key: NodeId,
+        live: &HashSet<NodeId>,
+    ) -> Vec<NodeId> {
+        let (tree, node_id) = match side {
+            None => (self.base, self.base.get(key).map(|_| key)),
+            Some(side) => (self.tree(side), self.side_id(side, key)),
+        };
+        let Some(node_id) = node_id else {
+            return Vec::new();
+        };
+        tree.get(node_id)
+            .expect("rooted node")
+            .children()
+            .map(|child| match side {
+                None => child.node_id(),
+                Some(side) => self.key(side, child.node_id()),
+            })
+            .filter(|child| live.contains(child))
+            .collect()
+    }
+
+    fn build(mut self, mut children: HashMap<NodeId, Vec<NodeId>>) -> Tree<T> {
+        let mut tree = TreeBuilder::new().with_capacity(self.data.len()).build();
+        let root_key = self.base.root_id.expect("base has a root");
+        if self.fates.get(&root_key) != Some(&Fate::Live) {
+            return tree;
+        }
+        let root_data = self.data.remove(&root_key).expect("live node has data");
+        let mut stack = vec![(root_key, tree.set_root(root_data))];
+        while let Some((key, node_id)) = stack.pop() {
+            let mut node = tree.get_mut(node_id).expect("node was just inserted");
+            let child_keys = children.remove(&key).unwrap_or_default();
+            let data = child_keys
+                .iter()
+                .map(|child| self.data.remove(child).expect("live node has data"));
+            let child_ids = node.extend_children(data);
+            stack.extend(child_keys.into_iter().zip(child_ids).rev());
+        }
+        tree
+    }
+}
+
+fn rooted_ids<T>(tree: &Tree<T>) -> Vec<NodeId> {
+    tree.root_id
+        .map(|root_id| {
+            tree.subtree_ids(root_id, Order::Pre)
+                .into_iter()
+                .map(|(_, node_id)| node_id)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg_attr(tarpaulin, skip)]
+#[cfg(test)]
+mod merge_tests {
+    use super::*;
+    use crate::test_support::{Rng, mutate, random_tree};
+    use crate::tree;
+
+    fn merge(base: &Tree<i32>, ours: &Tree<i32>, theirs: &Tree<i32>) -> Tree<i32> {
+        Tree::merge3(base, ours, theirs, |_| None).unwrap()
+    }
+
+    fn conflicts(base: &Tree<i32>, ours: &Tree<i32>, theirs: &Tree<i32>) -> Vec<Conflict<i32>> {
+        Tree::merge3(base, ours, theirs, |_| None).unwrap_err()
+    }
+
+    #[test]
+    fn concurrent_edits() {
+        let base = tree!(0 => [1 => [2, 3], 4 => [5], 6]);
+        // ours: inserts 7 under 1, deletes 6, updates 5
+        let ours = tree!(0 => [1 => [2, 3, 7], 4 => [50]]);
+        // theirs: moves 3 under 4, reorders the children of the root, inserts 8
+        let theirs = tree!(0 => [4 => [5, 3], 1 => [2], 6, 8]);
+        assert_eq!(
+            merge(&base, &ours, &theirs),
+            tree!(0 => [4 => [50, 3], 1 => [2, 7], 8])
+        );
+    }
+
+    #[test]
+    fn equal_additions_are_kept_once() {
+        let base = tree!(0 => [1]);
+        let ours = tree!(0 => [1, 2 => [3]]);
+        let theirs = tree!(0 => [2 => [3], 1]);
+        assert_eq!(merge(&base, &ours, &theirs), tree!(0 => [1, 2 => [3]]));
+
+        // different additions at the same place come ours first
+        let theirs = tree!(0 => [1, 2 => [4]]);
+        assert_eq!(
+            merge(&base, &ours, &theirs),
+            tree!(0 => [1, 2 => [3], 2 => [4]])
+        );
+        let ours = tree!(0 => [5, 1]);
+        let theirs = tree!(0 => [6, 1, 7]);
+        assert_eq!(merge(&base, &ours, &theirs), tree!(0 => [5, 6, 1, 7]));
+    }
+
+    #[test]
+    fn update_conflict() {
+        tree!(let base = 0 => [1, two @ 2]);
+        let ours = tree!(0 => [1, 20]);
+        let theirs = tree!(0 => [1, 21]);
+        assert_eq!(
+            conflicts(&base, &ours, &theirs),
+            vec![Conflict {
+                node_id: Some(two),
+                path: vec![1],
+                kind: ConflictKind::Update {
+                    base: 2,
+                    ours: 20,
+                    theirs: 21
+                },
+            }]
+        );
+        let merged = Tree::merge3(&base, &ours, &theirs, |conflict| match conflict.kind {
+            ConflictKind::Update { ours, theirs, .. } => Some(Resolution::Data(ours + theirs)),
+            _ => None,
+        });
+        assert_eq!(merged.unwrap(), tree!(0 => [1, 41]));
+    }
+
+    #[test]
+    fn delete_modify_conflict() {
+        tree!(let base = 0 => [one @ 1 => [2], 3]);
+        let ours = tree!(0 => [3]);
+        let theirs = tree!(0 => [1 => [20], 3]);
+
+        let found = conflicts(&base, &ours, &theirs);
+        let kinds: Vec<_> = found
+            .iter()
+            .map(|c| (c.path.clone(), c.kind.clone()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (vec![0], ConflictKind::DeletedByOurs),
+                (vec![0, 0], ConflictKind::DeletedByOurs),
+            ]
+        );
+        assert_eq!(found[0].node_id, Some(one));
+
+        let ours_wins = Tree::merge3(&base, &ours, &theirs, |_| Some(Resolution::Ours));
+        assert_eq!(ours_wins.unwrap(), ours);
+        let theirs_wins = Tree::merge3(&base, &ours, &theirs, |_| Some(Resolution::Theirs));
+        assert_eq!(theirs_wins.unwrap(), theirs);
+
+        // a deleted node the other side didn't touch stays deleted
+        let theirs = tree!(0 => [1 => [2], 3, 4]);
+        assert_eq!(merge(&base, &ours, &theirs), tree!(0 => [3, 4]));
+
+        // inserting under a node the other side deleted contests the deletion
+        let theirs = tree!(0 => [1 => [2, 5], 3]);
+        let found = conflicts(&base, &ours, &theirs);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, ConflictKind::DeletedByOurs);
+    }
+
+    #[test]
+    fn move_conflict() {
+        let base = tree!(0 => [1, 2, 3]);
+        let ours = tree!(0 => [1 => [3], 2]);
+        let theirs = tree!(0 => [1, 2 => [3]]);
+        let found = conflicts(&base, &ours, &theirs);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, ConflictKind::Move);
+        assert_eq!(found[0].path, vec![2]);
+
+        let merged = Tree::merge3(&base, &ours, &theirs, |_| Some(Resolution::Theirs));
+        assert_eq!(merged.unwrap(), theirs);
+        let data = Tree::merge3(&base, &ours, &theirs, |_| Some(Resolution::Data(9)));
+        assert_eq!(data.unwrap_err().len(), 1);
+    }
+
+    #[test]
+    fn cycle_conflict() {
+        let base = tree!(0 => [1 => [3], 2 => [4]]);
+        let ours = tree!(0 => [1 => [3, 2 => [4]]]);
+        let theirs = tree!(0 => [2 => [4, 1 => [3]]]);
+        let found = conflicts(&base, &ours, &theirs);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, ConflictKind::Cycle);
+        assert_eq!(found[0].path, vec![0]);
+
+        let merged = Tree::merge3(&base, &ours, &theirs, |_| Some(Resolution::Ours));
+        assert_eq!(merged.unwrap(), ours);
+    }
+
+    #[test]
+    fn cycle_left_behind_by_one_side() {
+        // picking our parents for the cycle of 1 and 3 makes 1, 3 and 2 a cycle
+        tree!(let base = 0 => [one @ 1, 2 => [3, 4]]);
+        let ours = tree!(0 => [2 => [3 => [1], 4]]);
+        let theirs = tree!(0 => [1 => [2 => [4], 3]]);
+
+        let mut asked = Vec::new();
+        let found = Tree::merge3(&base, &ours, &theirs, |conflict| {
+            asked.push(conflict.clone());
+            Some(Resolution::Ours)
+        })
+        .unwrap_err();
+        let cycle = Conflict {
+            node_id: Some(one),
+            path: vec![0],
+            kind: ConflictKind::Cycle,
+        };
+        assert_eq!(asked, vec![cycle.clone(), cycle.clone()]);
+        assert_eq!(found, vec![cycle]);
+
+        let mut answers = vec![Resolution::Theirs, Resolution::Ours];
+        let merged = Tree::merge3(&base, &ours, &theirs, |_| answers.pop());
+        assert_eq!(merged.unwrap(), theirs);
+    }
+
+    #[test]
+    fn deleted_parent_takes_contested_children() {
+        tree!(let base = 0 => [one @ 1 => [2], 3]);
+        let ours = tree!(0 => [3]);
+        let theirs = tree!(0 => [10 => [20], 3]);
+
+        // keeping the deletion of 1 settles its child too, whatever the resolver would say
+        let mut asked = Vec::new();
+        let merged = Tree::merge3(&base, &ours, &theirs, |conflict| {
+            asked.push(conflict.path.clone());
+            if conflict.path == [0] {
+                Some(Resolution::Ours)
+            } else {
+                Some(Resolution::Theirs)
+            }
+        });
+        assert_eq!(merged.unwrap(), ours);
+        assert_eq!(asked, vec![vec![0]]);
+
+        let merged = Tree::merge3(&base, &ours, &theirs, |conflict| {
+            if conflict.node_id == Some(one) {
+                Some(Resolution::Theirs)
+            } else {
+                Some(Resolution::Ours)
+            }
+        });
+        assert_eq!(merged.unwrap(), tree!(0 => [10, 3]));
+    }
+
+    #[test]
+    fn order_conflict() {
+        let base = tree!(0 => [1, 2, 3]);
+        let ours = tree!(0 => [3, 1, 2]);
+        let theirs = tree!(0 => [2, 3, 1]);
+        let found = conflicts(&base, &ours, &theirs);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, ConflictKind::Order);
+        assert!(found[0].path.is_empty());
+
+        let merged = Tree::merge3(&base, &ours, &theirs, |_| Some(Resolution::Theirs));
+        assert_eq!(merged.unwrap(), theirs);
+    }
+
+    #[test]
+    fn empty_trees() {
+        let empty: Tree<i32> = Tree::new();
+        let tree = tree!(0 => [1]);
+        assert_eq!(merge(&empty, &empty, &tree), tree);
+        assert_eq!(merge(&empty, &tree, &empty), tree);
+        assert_eq!(merge(&tree, &empty, &tree), empty);
+        assert_eq!(merge(&tree, &empty, &empty), empty);
+
+        let other = tree!(0 => [2]);
+        let found = conflicts(&empty, &tree, &other);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, ConflictKind::AddAdd);
+        assert_eq!(found[0].node_id, None);
+        let merged = Tree::merge3(&empty, &tree, &other, |_| Some(Resolution::Theirs));
+        assert_eq!(merged.unwrap(), other);
+
+        // deleting the whole tree conflicts with changes made by the other side
+        let found = conflicts(&tree, &empty, &other);
+        assert!(found.iter().all(|c| c.kind == ConflictKind::DeletedByOurs));
+    }
+
+    #[test]
+    fn display() {
+        let conflict: Conflict<i32> = Conflict {
+            node_id: None,
+            path: vec![1, 0, 2],
+            kind: ConflictKind::Move,
+        };
+        assert_eq!(conflict.to_string(), "both sides moved the node at /1/0/2");
+    }
+
+    #[test]
+    fn one_sided_changes_are_taken() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..200 {
+            let size = 1 + rng.below(25);
+            let base = random_tree(&mut rng, size);
+            let mut changed = base.clone();
+            let steps = 1 + rng.below(8);
+            mutate(&mut rng, &mut changed, steps);
+
+            assert_eq!(merge(&base, &changed, &base), changed);
+            assert_eq!(merge(&base, &base, &changed), changed);
+            assert_eq!(merge(&base, &changed, &changed.clone()), changed);
+        }
+    }
+}
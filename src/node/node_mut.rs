@@ -1,8 +1,13 @@
+use crate::IdMap;
 use crate::NodeId;
+use crate::behaviors::Position;
 use crate::behaviors::RemoveBehavior;
+use crate::error::{GraftError, MoveError};
 use crate::node::Node;
 use crate::node::NodeRef;
 use crate::tree::Tree;
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
 
 ///
 /// A mutable reference to a given `Node`'s data and its relatives.
@@ -14,7 +19,7 @@ pub struct NodeMut<'a, T> {
 }
 
 impl<'a, T> NodeMut<'a, T> {
-    pub(crate) fn new(node_id: NodeId, tree: &mut Tree<T>) -> NodeMut<T> {
+    pub(crate) fn new(node_id: NodeId, tree: &mut Tree<T>) -> NodeMut<'_, T> {
         NodeMut { node_id, tree }
     }
 
@@ -76,7 +81,7 @@ impl<'a, T> NodeMut<'a, T> {
     /// assert!(root.parent().is_none());
     /// ```
     ///
-    pub fn parent(&mut self) -> Option<NodeMut<T>> {
+    pub fn parent(&mut self) -> Option<NodeMut<'_, T>> {
         self.get_self_as_node()
             .relatives
             .parent
@@ -96,7 +101,7 @@ impl<'a, T> NodeMut<'a, T> {
     /// assert!(root.prev_sibling().is_none());
     /// ```
     ///
-    pub fn prev_sibling(&mut self) -> Option<NodeMut<T>> {
+    pub fn prev_sibling(&mut self) -> Option<NodeMut<'_, T>> {
         self.get_self_as_node()
             .relatives
             .prev_sibling
@@ -116,7 +121,7 @@ impl<'a, T> NodeMut<'a, T> {
     /// assert!(root.next_sibling().is_none());
     /// ```
     ///
-    pub fn next_sibling(&mut self) -> Option<NodeMut<T>> {
+    pub fn next_sibling(&mut self) -> Option<NodeMut<'_, T>> {
         self.get_self_as_node()
             .relatives
             .next_sibling
@@ -136,7 +141,7 @@ impl<'a, T> NodeMut<'a, T> {
     /// assert!(root.first_child().is_none());
     /// ```
     ///
-    pub fn first_child(&mut self) -> Option<NodeMut<T>> {
+    pub fn first_child(&mut self) -> Option<NodeMut<'_, T>> {
         self.get_self_as_node()
             .relatives
             .first_child
@@ -156,7 +161,7 @@ impl<'a, T> NodeMut<'a, T> {
     /// assert!(root.last_child().is_none());
     /// ```
     ///
-    pub fn last_child(&mut self) -> Option<NodeMut<T>> {
+    pub fn last_child(&mut self) -> Option<NodeMut<'_, T>> {
         self.get_self_as_node()
             .relatives
             .last_child
@@ -224,7 +229,7 @@ impl<'a, T> NodeMut<'a, T> {
     /// ");
     /// ```
     ///
-    pub fn append(&mut self, data: T) -> NodeMut<T> {
+    pub fn append(&mut self, data: T) -> NodeMut<'_, T> {
         let new_id = self.tree.core_tree.insert(data);
         self.append_node_id(new_id)
     }
@@ -273,7 +278,7 @@ impl<'a, T> NodeMut<'a, T> {
     /// ");
     /// ```
     ///
-    pub fn append_orphaned(&mut self, orphan_id: NodeId) -> Option<NodeMut<T>> {
+    pub fn append_orphaned(&mut self, orphan_id: NodeId) -> Option<NodeMut<'_, T>> {
         let orphan = self.tree.get(orphan_id)?;
         if !orphan.is_orphan() {
             return None; // Orphan must not have a parent or be the root
@@ -281,12 +286,13 @@ impl<'a, T> NodeMut<'a, T> {
         Some(self.append_node_id(orphan_id))
     }
 
-    fn append_node_id(&mut self, node_id: NodeId) -> NodeMut<T> {
+    pub(crate) fn append_node_id(&mut self, node_id: NodeId) -> NodeMut<'_, T> {
         let relatives = self.tree.get_node_relatives(self.node_id);
 
         let prev_sibling = relatives.last_child;
         self.tree.set_parent(node_id, Some(self.node_id));
         self.tree.set_prev_sibling(node_id, prev_sibling);
+        self.tree.set_next_sibling(node_id, None);
 
         let first_child = relatives.first_child.or(Some(node_id));
         self.tree.set_first_child(self.node_id, first_child);
@@ -323,7 +329,7 @@ impl<'a, T> NodeMut<'a, T> {
     /// assert_eq!(child.parent().unwrap().data(), &mut 1);
     /// ```
     ///
-    pub fn prepend(&mut self, data: T) -> NodeMut<T> {
+    pub fn prepend(&mut self, data: T) -> NodeMut<'_, T> {
         let new_id = self.tree.core_tree.insert(data);
         self.prepend_node_id(new_id)
     }
@@ -356,7 +362,7 @@ impl<'a, T> NodeMut<'a, T> {
     /// assert_eq!(child.parent().unwrap().data(), &mut 1);
     /// ```
     ///
-    pub fn prepend_orphaned(&mut self, orphan_id: NodeId) -> Option<NodeMut<T>> {
+    pub fn prepend_orphaned(&mut self, orphan_id: NodeId) -> Option<NodeMut<'_, T>> {
         let orphan = self.tree.get(orphan_id)?;
         if !orphan.is_orphan() {
             return None; // Orphan must not have a parent or be the root
@@ -364,12 +370,13 @@ impl<'a, T> NodeMut<'a, T> {
         Some(self.prepend_node_id(orphan_id))
     }
 
-    fn prepend_node_id(&mut self, node_id: NodeId) -> NodeMut<T> {
+    pub(crate) fn prepend_node_id(&mut self, node_id: NodeId) -> NodeMut<'_, T> {
         let relatives = self.tree.get_node_relatives(self.node_id);
 
         let next_sibling = relatives.first_child;
         self.tree.set_parent(node_id, Some(self.node_id));
         self.tree.set_next_sibling(node_id, next_sibling);
+        self.tree.set_prev_sibling(node_id, None);
 
         let last_child = relatives.last_child.or(Some(node_id));
         self.tree.set_first_child(self.node_id, Some(node_id));
@@ -382,6 +389,200 @@ impl<'a, T> NodeMut<'a, T> {
         NodeMut::new(node_id, self.tree)
     }
 
+    ///
+    /// Appends a new `Node` for each item of `iter` after this `Node`'s last child, in order.
+    /// Space for the new `Node`s is reserved up front from the `Iterator`'s `size_hint` and they
+    /// are linked in one pass.  Each `Node` is linked as soon as it is inserted, so if `iter`
+    /// panics the children inserted so far stay in place.
+    ///
+    /// Returns the `NodeId`s of the new `Node`s, in order.  They come as a `Vec` rather than a
+    /// range: new `Node`s reuse the slots of removed ones, so their `NodeId`s aren't contiguous.
+    ///
+    /// `NodeMut` also implements `Extend`, which does the same without returning the `NodeId`s.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// root.append(1);
+    ///
+    /// let new_ids = root.extend_children(2..5);
+    /// assert_eq!(new_ids.len(), 3);
+    /// root.extend(vec![5, 6]);
+    ///
+    /// assert_eq!(tree.get(new_ids[0]).unwrap().prev_sibling().unwrap().data(), &1);
+    /// assert_eq!(
+    ///     tree.root().unwrap().children().map(|child_ref| *child_ref.data())
+    ///         .collect::<Vec<i32>>(),
+    ///     vec![1, 2, 3, 4, 5, 6]);
+    /// ```
+    ///
+    pub fn extend_children<I>(&mut self, iter: I) -> Vec<NodeId>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let last_child = self.tree.get_node_relatives(self.node_id).last_child;
+        self.insert_children(iter, last_child, None)
+    }
+
+    ///
+    /// Inserts a new `Node` for each item of `iter` before this `Node`'s first child, keeping the
+    /// order of `iter` (the first item becomes the first child).  Space for the new `Node`s is
+    /// reserved up front from the `Iterator`'s `size_hint` and they are linked in one pass, each
+    /// as soon as it is inserted.
+    ///
+    /// Returns the `NodeId`s of the new `Node`s, in order.  As with `extend_children`, they come
+    /// as a `Vec` rather than a range because new `Node`s reuse the slots of removed ones.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// root.append(4);
+    ///
+    /// let new_ids = root.prepend_children(1..4);
+    /// assert_eq!(tree.get(new_ids[2]).unwrap().next_sibling().unwrap().data(), &4);
+    /// assert_eq!(
+    ///     tree.root().unwrap().children().map(|child_ref| *child_ref.data())
+    ///         .collect::<Vec<i32>>(),
+    ///     vec![1, 2, 3, 4]);
+    /// ```
+    ///
+    pub fn prepend_children<I>(&mut self, iter: I) -> Vec<NodeId>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let first_child = self.tree.get_node_relatives(self.node_id).first_child;
+        self.insert_children(iter, None, first_child)
+    }
+
+    ///
+    /// Inserts a new `Node` as this `Node`'s previous sibling.
+    /// Returns `Some(NodeMut)` pointing to the newly added `Node`.  Returns `None` (and drops
+    /// `data`) if this `Node` has no parent, i.e. it is the root or an orphan.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(1).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// root.append(2);
+    /// let four_id = root.append(4).node_id();
+    ///
+    /// let mut four = tree.get_mut(four_id).unwrap();
+    /// assert_eq!(four.insert_before(3).unwrap().data(), &mut 3);
+    /// assert_eq!(
+    ///     tree.root().unwrap().children().map(|child_ref| *child_ref.data())
+    ///         .collect::<Vec<i32>>(),
+    ///     vec![2, 3, 4]);
+    ///
+    /// assert!(tree.root_mut().unwrap().insert_before(0).is_none());
+    /// ```
+    ///
+    pub fn insert_before(&mut self, data: T) -> Option<NodeMut<'_, T>> {
+        self.get_self_as_node().relatives.parent?;
+        let new_id = self.tree.core_tree.insert(data);
+        self.tree.link_before(new_id, self.node_id);
+        Some(NodeMut::new(new_id, self.tree))
+    }
+
+    ///
+    /// Inserts an orphaned `Node` as this `Node`'s previous sibling.
+    /// Returns `Some(NodeMut)` pointing to the newly adopted `Node` if it exists and was orphaned.
+    /// Returns `None` if the `Node` was not orphaned or doesn't exist, or if this `Node` has no
+    /// parent.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(1).build();
+    /// let three_id = tree.root_mut().unwrap().append(3).node_id();
+    ///
+    /// let two_id = tree.insert_orphaned(2);
+    /// let mut three = tree.get_mut(three_id).unwrap();
+    /// assert!(three.insert_before_orphaned(two_id).is_some());
+    ///
+    /// let root = tree.root().unwrap();
+    /// assert_eq!(root.first_child().unwrap().data(), &2);
+    /// assert_eq!(root.last_child().unwrap().data(), &3);
+    ///
+    /// let mut three = tree.get_mut(three_id).unwrap();
+    /// assert!(three.insert_before_orphaned(two_id).is_none());
+    /// ```
+    ///
+    pub fn insert_before_orphaned(&mut self, orphan_id: NodeId) -> Option<NodeMut<'_, T>> {
+        self.get_self_as_node().relatives.parent?;
+        let orphan = self.tree.get(orphan_id)?;
+        if !orphan.is_orphan() || self.has_ancestor(orphan_id) {
+            return None; // Orphan must not have a parent, be the root or contain this node
+        }
+        self.tree.link_before(orphan_id, self.node_id);
+        Some(NodeMut::new(orphan_id, self.tree))
+    }
+
+    ///
+    /// Inserts a new `Node` as this `Node`'s next sibling.
+    /// Returns `Some(NodeMut)` pointing to the newly added `Node`.  Returns `None` (and drops
+    /// `data`) if this `Node` has no parent, i.e. it is the root or an orphan.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(1).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// let two_id = root.append(2).node_id();
+    /// root.append(4);
+    ///
+    /// let mut two = tree.get_mut(two_id).unwrap();
+    /// assert_eq!(two.insert_after(3).unwrap().data(), &mut 3);
+    /// assert_eq!(
+    ///     tree.root().unwrap().children().map(|child_ref| *child_ref.data())
+    ///         .collect::<Vec<i32>>(),
+    ///     vec![2, 3, 4]);
+    ///
+    /// assert!(tree.root_mut().unwrap().insert_after(5).is_none());
+    /// ```
+    ///
+    pub fn insert_after(&mut self, data: T) -> Option<NodeMut<'_, T>> {
+        self.get_self_as_node().relatives.parent?;
+        let new_id = self.tree.core_tree.insert(data);
+        self.tree.link_after(new_id, self.node_id);
+        Some(NodeMut::new(new_id, self.tree))
+    }
+
+    ///
+    /// Inserts an orphaned `Node` as this `Node`'s next sibling.
+    /// Returns `Some(NodeMut)` pointing to the newly adopted `Node` if it exists and was orphaned.
+    /// Returns `None` if the `Node` was not orphaned or doesn't exist, or if this `Node` has no
+    /// parent.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(1).build();
+    /// let two_id = tree.root_mut().unwrap().append(2).node_id();
+    ///
+    /// let three_id = tree.insert_orphaned(3);
+    /// let mut two = tree.get_mut(two_id).unwrap();
+    /// assert!(two.insert_after_orphaned(three_id).is_some());
+    ///
+    /// let root = tree.root().unwrap();
+    /// assert_eq!(root.first_child().unwrap().data(), &2);
+    /// assert_eq!(root.last_child().unwrap().data(), &3);
+    /// ```
+    ///
+    pub fn insert_after_orphaned(&mut self, orphan_id: NodeId) -> Option<NodeMut<'_, T>> {
+        self.get_self_as_node().relatives.parent?;
+        let orphan = self.tree.get(orphan_id)?;
+        if !orphan.is_orphan() || self.has_ancestor(orphan_id) {
+            return None; // Orphan must not have a parent, be the root or contain this node
+        }
+        self.tree.link_after(orphan_id, self.node_id);
+        Some(NodeMut::new(orphan_id, self.tree))
+    }
+
     ///
     /// Remove the first child of this `Node` and return the data that child contained.
     /// Returns a `Some`-value if this `Node` has a child to remove; returns a `None`-value
@@ -456,6 +657,339 @@ impl<'a, T> NodeMut<'a, T> {
         self.tree.remove(last_id, behavior)
     }
 
+    ///
+    /// Detaches this `Node` from its parent and siblings without removing it from the `Tree`.
+    /// The `Node` and its whole sub-tree stay alive as an orphan and can be re-attached later with
+    /// `append_orphaned` or `prepend_orphaned`. Detaching the root leaves the `Tree` without a
+    /// root.
+    ///
+    /// Returns `true` if this `Node` was detached, returns `false` if it was already an orphan.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(1).build();
+    /// let (two_id, three_id) = {
+    ///     let mut root = tree.root_mut().expect("root doesn't exist?");
+    ///     let two_id = root.append(2).node_id();
+    ///     let three_id = root.append(3).node_id();
+    ///     root.append(4);
+    ///     (two_id, three_id)
+    /// };
+    /// tree.get_mut(three_id).unwrap().append(5);
+    ///
+    /// assert!(tree.get_mut(three_id).unwrap().detach());
+    /// assert!(tree.get(three_id).unwrap().is_orphan());
+    /// assert_eq!(
+    ///     tree.root().unwrap().children().map(|child_ref| *child_ref.data())
+    ///         .collect::<Vec<i32>>(),
+    ///     vec![2, 4]);
+    ///
+    /// tree.get_mut(two_id).unwrap().append_orphaned(three_id).unwrap();
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 1
+    /// ├── 2
+    /// │   └── 3
+    /// │       └── 5
+    /// └── 4
+    /// ");
+    /// ```
+    ///
+    pub fn detach(&mut self) -> bool {
+        if self.is_orphan() {
+            return false;
+        }
+        if self.tree.root_id == Some(self.node_id) {
+            self.tree.root_id = None;
+        }
+        self.tree.unlink(self.node_id);
+        true
+    }
+
+    ///
+    /// Moves this `Node` (along with its whole sub-tree) under `new_parent` at the given
+    /// `Position`.  Only the links around the old and new positions are updated, so `NodeId`s in
+    /// the moved sub-tree stay valid.  Orphans can be moved too, which attaches them.
+    ///
+    /// Returns an `Err` and leaves the `Tree` untouched if `new_parent` doesn't exist, if the
+    /// `Position` doesn't refer to one of `new_parent`'s children, if `new_parent` is this `Node`
+    /// or one of its descendants, or if this `Node` is the root.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    /// use nary_tree::behaviors::Position;
+    /// use nary_tree::error::MoveError;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let (one_id, two_id, three_id) = {
+    ///     let mut root = tree.root_mut().expect("root doesn't exist?");
+    ///     let one_id = root.append(1).node_id();
+    ///     let two_id = root.append(2).node_id();
+    ///     let three_id = root.append(3).node_id();
+    ///     (one_id, two_id, three_id)
+    /// };
+    /// let four_id = tree.get_mut(one_id).unwrap().append(4).node_id();
+    ///
+    /// tree.get_mut(three_id).unwrap().move_to(one_id, Position::Before(four_id)).unwrap();
+    /// tree.get_mut(two_id).unwrap().move_to(three_id, Position::Last).unwrap();
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 0
+    /// └── 1
+    ///     ├── 3
+    ///     │   └── 2
+    ///     └── 4
+    /// ");
+    ///
+    /// let result = tree.get_mut(one_id).unwrap().move_to(two_id, Position::First);
+    /// assert_eq!(result, Err(MoveError::WouldCreateCycle));
+    /// ```
+    ///
+    pub fn move_to(&mut self, new_parent: NodeId, position: Position) -> Result<(), MoveError> {
+        let node_id = self.node_id;
+        if self.tree.get_node(new_parent).is_none() {
+            return Err(MoveError::ParentNotFound(new_parent));
+        }
+        if self.tree.root_id == Some(node_id) {
+            return Err(MoveError::RootNotMovable);
+        }
+        if std::iter::once(new_parent)
+            .chain(
+                self.tree
+                    .get(new_parent)
+                    .unwrap()
+                    .ancestors()
+                    .map(|a| a.node_id()),
+            )
+            .any(|id| id == node_id)
+        {
+            return Err(MoveError::WouldCreateCycle);
+        }
+
+        let position = self
+            .tree
+            .check_position(new_parent, Some(node_id), position)?;
+        if position == Position::Before(node_id) || position == Position::After(node_id) {
+            return Ok(());
+        }
+
+        self.tree.unlink(node_id);
+        self.tree.link_at(node_id, new_parent, position);
+        Ok(())
+    }
+
+    ///
+    /// Inserts a new `Node` between this `Node` and its parent, at this `Node`'s position among
+    /// its siblings, so that this `Node` becomes the new `Node`'s only child.  If this `Node` is
+    /// the root, the new `Node` becomes the root.  Returns a `NodeMut` pointing to the new `Node`.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let two_id = {
+    ///     let mut root = tree.root_mut().expect("root doesn't exist?");
+    ///     root.append(1);
+    ///     let two_id = root.append(2).node_id();
+    ///     root.append(3);
+    ///     two_id
+    /// };
+    ///
+    /// tree.get_mut(two_id).unwrap().wrap(20);
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 0
+    /// ├── 1
+    /// ├── 20
+    /// │   └── 2
+    /// └── 3
+    /// ");
+    /// ```
+    ///
+    pub fn wrap(&mut self, data: T) -> NodeMut<'_, T> {
+        let node_id = self.node_id;
+        if self.tree.root_id == Some(node_id) {
+            let new_id = self.tree.set_root(data);
+            return NodeMut::new(new_id, self.tree);
+        }
+
+        let new_id = self.tree.core_tree.insert(data);
+        if self.get_self_as_node().relatives.parent.is_some() {
+            self.tree.link_before(new_id, node_id);
+            self.tree.unlink(node_id);
+        }
+        let mut new_node = NodeMut::new(new_id, self.tree);
+        new_node.append_node_id(node_id);
+        new_node
+    }
+
+    ///
+    /// Moves the children of this `Node` whose indexes are in `range` under a new `Node`, which
+    /// takes their place among this `Node`'s children.  Returns `Some(NodeMut)` pointing to the new
+    /// `Node`, or `None` (dropping `data`) if `range` goes past the end of this `Node`'s children.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// for i in 1..5 {
+    ///     root.append(i);
+    /// }
+    ///
+    /// assert!(root.group_children(1..3, 23).is_some());
+    /// assert!(root.group_children(2..4, 0).is_none());
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 0
+    /// ├── 1
+    /// ├── 23
+    /// │   ├── 2
+    /// │   └── 3
+    /// └── 4
+    /// ");
+    /// ```
+    ///
+    pub fn group_children<R>(&mut self, range: R, data: T) -> Option<NodeMut<'_, T>>
+    where
+        R: RangeBounds<usize>,
+    {
+        let child_ids = self.child_ids();
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1)?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1)?,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => child_ids.len(),
+        };
+        if start > end || end > child_ids.len() {
+            return None;
+        }
+
+        let new_id = self.tree.core_tree.insert(data);
+        for &child_id in &child_ids[start..end] {
+            self.tree.unlink(child_id);
+        }
+        match child_ids.get(end) {
+            Some(&next_id) => self.tree.link_before(new_id, next_id),
+            None => {
+                self.append_node_id(new_id);
+            }
+        }
+        let mut new_node = NodeMut::new(new_id, self.tree);
+        for &child_id in &child_ids[start..end] {
+            new_node.append_node_id(child_id);
+        }
+        Some(new_node)
+    }
+
+    ///
+    /// Moves every `Node` of `other` into this `Tree` and attaches `other`'s root as a child of
+    /// this `Node` at the given `Position`.  Orphans of `other` are moved as well and stay
+    /// orphaned.
+    ///
+    /// Returns a map from the `NodeId`s the `Node`s had in `other` to their new `NodeId`s in this
+    /// `Tree`.  Returns a `GraftError` holding `other` if the `Position` doesn't refer to one of
+    /// this `Node`'s children.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    /// use nary_tree::behaviors::Position;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let three_id = {
+    ///     let mut root = tree.root_mut().expect("root doesn't exist?");
+    ///     root.append(1);
+    ///     root.append(3).node_id()
+    /// };
+    ///
+    /// let mut other = TreeBuilder::new().with_root(2).build();
+    /// let four_id = other.root_mut().unwrap().append(4).node_id();
+    ///
+    /// let mut root = tree.root_mut().unwrap();
+    /// let id_map = root.graft(other, Position::Before(three_id)).unwrap();
+    /// assert_eq!(tree.get(id_map[&four_id]).unwrap().data(), &4);
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 0
+    /// ├── 1
+    /// ├── 2
+    /// │   └── 4
+    /// └── 3
+    /// ");
+    ///
+    /// let other = TreeBuilder::new().with_root(5).build();
+    /// let mut root = tree.root_mut().unwrap();
+    /// let error = root.graft(other, Position::Index(9)).unwrap_err();
+    /// assert_eq!(error.tree.root().unwrap().data(), &5);
+    /// ```
+    ///
+    // the error carries `other` back by value rather than boxing it
+    #[allow(clippy::result_large_err)]
+    pub fn graft(&mut self, other: Tree<T>, position: Position) -> Result<IdMap, GraftError<T>> {
+        let position = match self.tree.check_position(self.node_id, None, position) {
+            Ok(position) => position,
+            Err(error) => return Err(GraftError { error, tree: other }),
+        };
+        let other_root_id = other.root_id;
+        let id_map = self.tree.absorb(other.core_tree);
+        if let Some(root_id) = other_root_id {
+            self.tree.link_at(id_map[&root_id], self.node_id, position);
+        }
+        Ok(id_map)
+    }
+
+    ///
+    /// Copies this `Node` and its descendants and inserts the copy as this `Node`'s next sibling.
+    /// Returns `Some(NodeMut)` pointing to the root of the copy, or `None` if this `Node` has no
+    /// parent.
+    ///
+    /// ```
+    /// use nary_tree::tree;
+    ///
+    /// tree!(let mut tree = 0 => [one @ 1 => [2], 3]);
+    ///
+    /// let mut one = tree.get_mut(one).unwrap();
+    /// *one.duplicate_subtree().unwrap().data() = 10;
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 0
+    /// ├── 1
+    /// │   └── 2
+    /// ├── 10
+    /// │   └── 2
+    /// └── 3
+    /// ");
+    /// ```
+    ///
+    pub fn duplicate_subtree(&mut self) -> Option<NodeMut<'_, T>>
+    where
+        T: Clone,
+    {
+        self.get_self_as_node().relatives.parent?;
+        let copy = self.tree.clone_subtree_from(self.node_id);
+        let copy_root_id = copy.root_id.expect("copy has a root");
+        let id_map = self.tree.absorb(copy.core_tree);
+        let new_id = id_map[&copy_root_id];
+        self.tree.link_after(new_id, self.node_id);
+        Some(NodeMut::new(new_id, self.tree))
+    }
+
     ///
     /// Returns a `NodeRef` pointing to this `NodeMut`.
     ///
@@ -471,7 +1005,7 @@ impl<'a, T> NodeMut<'a, T> {
     /// assert_eq!(root.data(), &1);
     /// ```
     ///
-    pub fn as_ref(&self) -> NodeRef<T> {
+    pub fn as_ref(&self) -> NodeRef<'_, T> {
         NodeRef::new(self.node_id, self.tree)
     }
 
@@ -834,21 +1368,312 @@ impl<'a, T> NodeMut<'a, T> {
         }
     }
 
-    fn get_self_as_node(&self) -> &Node<T> {
-        if let Some(node) = self.tree.get_node(self.node_id) {
-            node
-        } else {
-            unreachable!()
-        }
-    }
-}
-
-#[cfg_attr(tarpaulin, skip)]
-#[cfg(test)]
+    ///
+    /// Reverses the order of this `Node`'s children.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// root.append(1);
+    /// root.append(2);
+    /// root.append(3);
+    ///
+    /// root.reverse_children();
+    /// assert_eq!(
+    ///     tree.root().unwrap().children().map(|child_ref| *child_ref.data())
+    ///         .collect::<Vec<i32>>(),
+    ///     vec![3, 2, 1]);
+    /// ```
+    ///
+    pub fn reverse_children(&mut self) {
+        let mut child_ids = self.child_ids();
+        child_ids.reverse();
+        self.tree.relink_children(self.node_id, &child_ids);
+    }
+
+    ///
+    /// Rotates this `Node`'s children `k` places to the left, so that the child at index `k`
+    /// becomes the first child.  `k` wraps around the number of children.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// root.append(1);
+    /// root.append(2);
+    /// root.append(3);
+    ///
+    /// root.rotate_children_left(1);
+    /// assert_eq!(
+    ///     tree.root().unwrap().children().map(|child_ref| *child_ref.data())
+    ///         .collect::<Vec<i32>>(),
+    ///     vec![2, 3, 1]);
+    /// ```
+    ///
+    pub fn rotate_children_left(&mut self, k: usize) {
+        let mut child_ids = self.child_ids();
+        if child_ids.is_empty() {
+            return;
+        }
+        let k = k % child_ids.len();
+        child_ids.rotate_left(k);
+        self.tree.relink_children(self.node_id, &child_ids);
+    }
+
+    ///
+    /// Rotates this `Node`'s children `k` places to the right, so that the last `k` children
+    /// become the first ones.  `k` wraps around the number of children.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// root.append(1);
+    /// root.append(2);
+    /// root.append(3);
+    ///
+    /// root.rotate_children_right(1);
+    /// assert_eq!(
+    ///     tree.root().unwrap().children().map(|child_ref| *child_ref.data())
+    ///         .collect::<Vec<i32>>(),
+    ///     vec![3, 1, 2]);
+    /// ```
+    ///
+    pub fn rotate_children_right(&mut self, k: usize) {
+        let mut child_ids = self.child_ids();
+        if child_ids.is_empty() {
+            return;
+        }
+        let k = k % child_ids.len();
+        child_ids.rotate_right(k);
+        self.tree.relink_children(self.node_id, &child_ids);
+    }
+
+    ///
+    /// Reorders this `Node`'s children so that the child at index `order[i]` ends up at index
+    /// `i`.
+    ///
+    /// Returns true if the children were reordered, returns false (leaving them untouched) if
+    /// `order` isn't a permutation of the indexes of this `Node`'s children.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// root.append(1);
+    /// root.append(2);
+    /// root.append(3);
+    ///
+    /// assert!(root.permute_children(&[2, 0, 1]));
+    /// assert_eq!(
+    ///     tree.root().unwrap().children().map(|child_ref| *child_ref.data())
+    ///         .collect::<Vec<i32>>(),
+    ///     vec![3, 1, 2]);
+    ///
+    /// let mut root = tree.root_mut().unwrap();
+    /// assert!(!root.permute_children(&[0, 0, 1]));
+    /// assert!(!root.permute_children(&[0, 1]));
+    /// ```
+    ///
+    pub fn permute_children(&mut self, order: &[usize]) -> bool {
+        let child_ids = self.child_ids();
+        if order.len() != child_ids.len() {
+            return false;
+        }
+        let mut seen = vec![false; order.len()];
+        for &index in order {
+            if index >= seen.len() || seen[index] {
+                return false;
+            }
+            seen[index] = true;
+        }
+        let permuted: Vec<NodeId> = order.iter().map(|&index| child_ids[index]).collect();
+        self.tree.relink_children(self.node_id, &permuted);
+        true
+    }
+
+    ///
+    /// Sorts the children of this `Node` with a comparator function.  The sort is stable and only
+    /// relinks the children, so their `NodeId`s stay valid.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// root.append(3);
+    /// root.append(1);
+    /// root.append(2);
+    ///
+    /// root.sort_children_by(|a, b| a.cmp(b));
+    /// assert_eq!(
+    ///     tree.root().unwrap().children().map(|child_ref| *child_ref.data())
+    ///         .collect::<Vec<i32>>(),
+    ///     vec![1, 2, 3]);
+    /// ```
+    ///
+    pub fn sort_children_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.tree.sort_children_by(self.node_id, &mut compare);
+    }
+
+    ///
+    /// Sorts the children of this `Node` with a key extraction function.  The sort is stable and
+    /// only relinks the children, so their `NodeId`s stay valid.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root("root").build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// root.append("ccc");
+    /// root.append("a");
+    /// root.append("bb");
+    ///
+    /// root.sort_children_by_key(|data| data.len());
+    /// assert_eq!(
+    ///     tree.root().unwrap().children().map(|child_ref| *child_ref.data())
+    ///         .collect::<Vec<&str>>(),
+    ///     vec!["a", "bb", "ccc"]);
+    /// ```
+    ///
+    pub fn sort_children_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        self.tree
+            .sort_children_by(self.node_id, &mut |a, b| f(a).cmp(&f(b)));
+    }
+
+    ///
+    /// Sorts the children of this `Node` and of every `Node` below it with a comparator function.
+    /// The sort is stable and only relinks the children, so their `NodeId`s stay valid.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// let mut two = root.append(2);
+    /// two.append(22);
+    /// two.append(21);
+    /// root.append(1);
+    ///
+    /// root.sort_subtree_by(|a, b| a.cmp(b));
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 0
+    /// ├── 1
+    /// └── 2
+    ///     ├── 21
+    ///     └── 22
+    /// ");
+    /// ```
+    ///
+    pub fn sort_subtree_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let sub_tree_ids: Vec<NodeId> = self
+            .as_ref()
+            .traverse_pre_order()
+            .map(|node_ref| node_ref.node_id())
+            .collect();
+        for node_id in sub_tree_ids {
+            self.tree.sort_children_by(node_id, &mut compare);
+        }
+    }
+
+    /// Inserts a new child for each item of `iter` between the children `prev_id` and `next_id`
+    /// (or at the start or end of the children when they are `None`), linking each one in turn.
+    fn insert_children<I>(
+        &mut self,
+        iter: I,
+        mut prev_id: Option<NodeId>,
+        next_id: Option<NodeId>,
+    ) -> Vec<NodeId>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        self.tree.core_tree.reserve(lower);
+
+        let mut node_ids = Vec::with_capacity(lower);
+        for data in iter {
+            let node_id = self.tree.core_tree.insert(data);
+            self.tree.set_parent(node_id, Some(self.node_id));
+            self.tree.set_prev_sibling(node_id, prev_id);
+            self.tree.set_next_sibling(node_id, next_id);
+            match prev_id {
+                Some(prev_id) => self.tree.set_next_sibling(prev_id, Some(node_id)),
+                None => self.tree.set_first_child(self.node_id, Some(node_id)),
+            }
+            match next_id {
+                Some(next_id) => self.tree.set_prev_sibling(next_id, Some(node_id)),
+                None => self.tree.set_last_child(self.node_id, Some(node_id)),
+            }
+            prev_id = Some(node_id);
+            node_ids.push(node_id);
+        }
+        node_ids
+    }
+
+    fn child_ids(&self) -> Vec<NodeId> {
+        self.as_ref()
+            .children()
+            .map(|node_ref| node_ref.node_id())
+            .collect()
+    }
+
+    fn has_ancestor(&self, ancestor_id: NodeId) -> bool {
+        self.as_ref()
+            .ancestors()
+            .any(|ancestor| ancestor.node_id() == ancestor_id)
+    }
+
+    fn get_self_as_node(&self) -> &Node<T> {
+        if let Some(node) = self.tree.get_node(self.node_id) {
+            node
+        } else {
+            unreachable!()
+        }
+    }
+}
+
+impl<T> Extend<T> for NodeMut<'_, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.extend_children(iter);
+    }
+}
+
+#[cfg_attr(tarpaulin, skip)]
+#[cfg(test)]
 mod node_mut_tests {
+    use crate::NodeId;
+    use crate::behaviors::Position;
     use crate::behaviors::RemoveBehavior::{DropChildren, OrphanChildren};
+    use crate::error::MoveError;
     use crate::tree::Tree;
 
+    fn child_ids(tree: &Tree<i32>, node_id: NodeId) -> Vec<NodeId> {
+        tree.get(node_id)
+            .unwrap()
+            .children()
+            .map(|child| child.node_id())
+            .collect()
+    }
+
     #[test]
     fn node_id() {
         let mut tree = Tree::new();
@@ -1615,6 +2440,792 @@ mod node_mut_tests {
         assert_eq!(orphan_ref.next_sibling().unwrap().node_id(), last_child_id);
     }
 
+    #[test]
+    fn detach_middle_child() {
+        let mut tree = Tree::new();
+        tree.set_root(1);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let two_id = root_mut.append(2).node_id();
+        let three_id = root_mut.append(3).node_id();
+        let four_id = root_mut.append(4).node_id();
+        let five_id = tree.get_mut(three_id).unwrap().append(5).node_id();
+
+        assert!(tree.get_mut(three_id).unwrap().detach());
+
+        let root_node = tree.get_node(root_id).unwrap();
+        assert_eq!(root_node.relatives.first_child, Some(two_id));
+        assert_eq!(root_node.relatives.last_child, Some(four_id));
+        assert_eq!(
+            tree.get_node(two_id).unwrap().relatives.next_sibling,
+            Some(four_id)
+        );
+        assert_eq!(
+            tree.get_node(four_id).unwrap().relatives.prev_sibling,
+            Some(two_id)
+        );
+
+        let three_node = tree.get_node(three_id).unwrap();
+        assert_eq!(three_node.relatives.parent, None);
+        assert_eq!(three_node.relatives.prev_sibling, None);
+        assert_eq!(three_node.relatives.next_sibling, None);
+        assert_eq!(three_node.relatives.first_child, Some(five_id));
+
+        let five = tree.get(five_id).unwrap();
+        assert_eq!(five.parent().unwrap().node_id(), three_id);
+        assert!(five.has_orphaned_ancestor());
+    }
+
+    #[test]
+    fn detach_first_and_last_child() {
+        let mut tree = Tree::new();
+        tree.set_root(1);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let two_id = root_mut.append(2).node_id();
+        let three_id = root_mut.append(3).node_id();
+        let four_id = root_mut.append(4).node_id();
+
+        assert!(tree.get_mut(two_id).unwrap().detach());
+        assert!(tree.get_mut(four_id).unwrap().detach());
+
+        let root_node = tree.get_node(root_id).unwrap();
+        assert_eq!(root_node.relatives.first_child, Some(three_id));
+        assert_eq!(root_node.relatives.last_child, Some(three_id));
+
+        let three_node = tree.get_node(three_id).unwrap();
+        assert_eq!(three_node.relatives.prev_sibling, None);
+        assert_eq!(three_node.relatives.next_sibling, None);
+    }
+
+    #[test]
+    fn detach_root() {
+        let mut tree = Tree::new();
+        tree.set_root(1);
+        let root_id = tree.root_id().unwrap();
+        let two_id = tree.root_mut().unwrap().append(2).node_id();
+
+        assert!(tree.get_mut(root_id).unwrap().detach());
+        assert!(tree.root_id().is_none());
+        assert!(tree.get(root_id).unwrap().is_orphan());
+        assert_eq!(
+            tree.get(two_id).unwrap().parent().unwrap().node_id(),
+            root_id
+        );
+    }
+
+    #[test]
+    fn detach_orphan() {
+        let mut tree = Tree::new();
+        tree.set_root(1);
+        let orphan_id = tree.insert_orphaned(10);
+
+        assert!(!tree.get_mut(orphan_id).unwrap().detach());
+        assert!(tree.get(orphan_id).unwrap().is_orphan());
+    }
+
+    #[test]
+    fn detach_and_prepend_orphaned() {
+        let mut tree = Tree::new();
+        tree.set_root(1);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let two_id = root_mut.append(2).node_id();
+        let three_id = root_mut.append(3).node_id();
+
+        assert!(tree.get_mut(three_id).unwrap().detach());
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        assert!(root_mut.prepend_orphaned(three_id).is_some());
+
+        let root_ref = tree.get(root_id).unwrap();
+        let children: Vec<_> = root_ref.children().map(|child| child.node_id()).collect();
+        assert_eq!(children, vec![three_id, two_id]);
+        assert_eq!(root_ref.last_child().unwrap().node_id(), two_id);
+        assert!(tree.get(three_id).unwrap().prev_sibling().is_none());
+        assert_eq!(
+            tree.get(two_id).unwrap().prev_sibling().unwrap().node_id(),
+            three_id
+        );
+        assert!(tree.get(two_id).unwrap().next_sibling().is_none());
+    }
+
+    #[test]
+    fn append_orphaned_clears_stale_siblings() {
+        let mut tree = Tree::new();
+        tree.set_root(1);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let two_id = root_mut.append(2).node_id();
+        let mut two_mut = tree.get_mut(two_id).unwrap();
+        let three_id = two_mut.append(3).node_id();
+        two_mut.append(4);
+
+        tree.remove(two_id, OrphanChildren);
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        assert!(root_mut.append_orphaned(three_id).is_some());
+
+        let root_ref = tree.get(root_id).unwrap();
+        assert_eq!(root_ref.children().count(), 1);
+        assert!(tree.get(three_id).unwrap().next_sibling().is_none());
+    }
+
+    #[test]
+    fn move_to_positions() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let one_id = root_mut.append(1).node_id();
+        let two_id = root_mut.append(2).node_id();
+        let three_id = root_mut.append(3).node_id();
+        let four_id = root_mut.append(4).node_id();
+
+        let mut two_mut = tree.get_mut(two_id).unwrap();
+        assert_eq!(two_mut.move_to(root_id, Position::First), Ok(()));
+        assert_eq!(
+            child_ids(&tree, root_id),
+            vec![two_id, one_id, three_id, four_id]
+        );
+
+        let mut two_mut = tree.get_mut(two_id).unwrap();
+        assert_eq!(two_mut.move_to(root_id, Position::Last), Ok(()));
+        assert_eq!(
+            child_ids(&tree, root_id),
+            vec![one_id, three_id, four_id, two_id]
+        );
+
+        let mut two_mut = tree.get_mut(two_id).unwrap();
+        assert_eq!(two_mut.move_to(root_id, Position::Before(three_id)), Ok(()));
+        assert_eq!(
+            child_ids(&tree, root_id),
+            vec![one_id, two_id, three_id, four_id]
+        );
+
+        let mut one_mut = tree.get_mut(one_id).unwrap();
+        assert_eq!(one_mut.move_to(root_id, Position::After(four_id)), Ok(()));
+        assert_eq!(
+            child_ids(&tree, root_id),
+            vec![two_id, three_id, four_id, one_id]
+        );
+
+        let mut one_mut = tree.get_mut(one_id).unwrap();
+        assert_eq!(one_mut.move_to(root_id, Position::Index(1)), Ok(()));
+        assert_eq!(
+            child_ids(&tree, root_id),
+            vec![two_id, one_id, three_id, four_id]
+        );
+
+        let mut one_mut = tree.get_mut(one_id).unwrap();
+        assert_eq!(one_mut.move_to(root_id, Position::Index(3)), Ok(()));
+        assert_eq!(
+            child_ids(&tree, root_id),
+            vec![two_id, three_id, four_id, one_id]
+        );
+
+        let root_node = tree.get_node(root_id).unwrap();
+        assert_eq!(root_node.relatives.first_child, Some(two_id));
+        assert_eq!(root_node.relatives.last_child, Some(one_id));
+        assert!(tree.get(two_id).unwrap().prev_sibling().is_none());
+        assert!(tree.get(one_id).unwrap().next_sibling().is_none());
+    }
+
+    #[test]
+    fn move_to_new_parent() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let one_id = root_mut.append(1).node_id();
+        let two_id = root_mut.append(2).node_id();
+        let three_id = tree.get_mut(two_id).unwrap().append(3).node_id();
+
+        let mut two_mut = tree.get_mut(two_id).unwrap();
+        assert_eq!(two_mut.move_to(one_id, Position::Index(0)), Ok(()));
+
+        assert_eq!(child_ids(&tree, root_id), vec![one_id]);
+        assert_eq!(child_ids(&tree, one_id), vec![two_id]);
+        assert_eq!(child_ids(&tree, two_id), vec![three_id]);
+        let two = tree.get(two_id).unwrap();
+        assert_eq!(two.parent().unwrap().node_id(), one_id);
+        assert!(two.prev_sibling().is_none());
+        assert!(two.next_sibling().is_none());
+    }
+
+    #[test]
+    fn move_to_errors() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let one_id = root_mut.append(1).node_id();
+        let two_id = root_mut.append(2).node_id();
+        let three_id = tree.get_mut(one_id).unwrap().append(3).node_id();
+        let removed_id = tree.get_mut(two_id).unwrap().append(4).node_id();
+        tree.remove(removed_id, DropChildren);
+
+        let mut one_mut = tree.get_mut(one_id).unwrap();
+        assert_eq!(
+            one_mut.move_to(three_id, Position::Last),
+            Err(MoveError::WouldCreateCycle)
+        );
+        assert_eq!(
+            one_mut.move_to(one_id, Position::Last),
+            Err(MoveError::WouldCreateCycle)
+        );
+        assert_eq!(
+            one_mut.move_to(removed_id, Position::Last),
+            Err(MoveError::ParentNotFound(removed_id))
+        );
+        assert_eq!(
+            one_mut.move_to(two_id, Position::Before(three_id)),
+            Err(MoveError::NotAChild(three_id))
+        );
+        assert_eq!(
+            one_mut.move_to(two_id, Position::Index(1)),
+            Err(MoveError::IndexOutOfBounds(1))
+        );
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        assert_eq!(
+            root_mut.move_to(root_id, Position::Last),
+            Err(MoveError::RootNotMovable)
+        );
+
+        assert_eq!(child_ids(&tree, root_id), vec![one_id, two_id]);
+        assert_eq!(child_ids(&tree, one_id), vec![three_id]);
+    }
+
+    #[test]
+    fn move_to_relative_to_self() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let one_id = root_mut.append(1).node_id();
+        let two_id = root_mut.append(2).node_id();
+
+        let mut one_mut = tree.get_mut(one_id).unwrap();
+        assert_eq!(one_mut.move_to(root_id, Position::After(one_id)), Ok(()));
+        assert_eq!(child_ids(&tree, root_id), vec![one_id, two_id]);
+    }
+
+    #[test]
+    fn insert_before_first_child() {
+        let mut tree = Tree::new();
+        tree.set_root(1);
+        let root_id = tree.root_id().unwrap();
+
+        let two_id = tree.get_mut(root_id).unwrap().append(2).node_id();
+        let new_id = tree
+            .get_mut(two_id)
+            .unwrap()
+            .insert_before(3)
+            .unwrap()
+            .node_id();
+
+        let root_node = tree.get_node(root_id).unwrap();
+        assert_eq!(root_node.relatives.first_child, Some(new_id));
+        assert_eq!(root_node.relatives.last_child, Some(two_id));
+
+        let new_node = tree.get_node(new_id).unwrap();
+        assert_eq!(new_node.relatives.parent, Some(root_id));
+        assert_eq!(new_node.relatives.prev_sibling, None);
+        assert_eq!(new_node.relatives.next_sibling, Some(two_id));
+        assert_eq!(
+            tree.get_node(two_id).unwrap().relatives.prev_sibling,
+            Some(new_id)
+        );
+    }
+
+    #[test]
+    fn insert_after_last_child() {
+        let mut tree = Tree::new();
+        tree.set_root(1);
+        let root_id = tree.root_id().unwrap();
+
+        let two_id = tree.get_mut(root_id).unwrap().append(2).node_id();
+        let new_id = tree
+            .get_mut(two_id)
+            .unwrap()
+            .insert_after(3)
+            .unwrap()
+            .node_id();
+
+        let root_node = tree.get_node(root_id).unwrap();
+        assert_eq!(root_node.relatives.first_child, Some(two_id));
+        assert_eq!(root_node.relatives.last_child, Some(new_id));
+
+        let new_node = tree.get_node(new_id).unwrap();
+        assert_eq!(new_node.relatives.parent, Some(root_id));
+        assert_eq!(new_node.relatives.prev_sibling, Some(two_id));
+        assert_eq!(new_node.relatives.next_sibling, None);
+        assert_eq!(
+            tree.get_node(two_id).unwrap().relatives.next_sibling,
+            Some(new_id)
+        );
+    }
+
+    #[test]
+    fn insert_between_children() {
+        let mut tree = Tree::new();
+        tree.set_root(1);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let two_id = root_mut.append(2).node_id();
+        let five_id = root_mut.append(5).node_id();
+
+        let three_id = tree
+            .get_mut(two_id)
+            .unwrap()
+            .insert_after(3)
+            .unwrap()
+            .node_id();
+        let four_id = tree
+            .get_mut(five_id)
+            .unwrap()
+            .insert_before(4)
+            .unwrap()
+            .node_id();
+
+        assert_eq!(
+            child_ids(&tree, root_id),
+            vec![two_id, three_id, four_id, five_id]
+        );
+        assert_eq!(
+            tree.get_node(five_id).unwrap().relatives.prev_sibling,
+            Some(four_id)
+        );
+    }
+
+    #[test]
+    fn insert_sibling_of_root_or_orphan() {
+        let mut tree = Tree::new();
+        tree.set_root(1);
+        let orphan_id = tree.insert_orphaned(10);
+        let other_id = tree.insert_orphaned(11);
+
+        assert!(tree.root_mut().unwrap().insert_before(2).is_none());
+        assert!(tree.root_mut().unwrap().insert_after(2).is_none());
+        let mut orphan_mut = tree.get_mut(orphan_id).unwrap();
+        assert!(orphan_mut.insert_before(2).is_none());
+        assert!(orphan_mut.insert_after_orphaned(other_id).is_none());
+        assert!(tree.root().unwrap().first_child().is_none());
+    }
+
+    #[test]
+    fn insert_orphaned_sibling() {
+        let mut tree = Tree::new();
+        tree.set_root(1);
+        let root_id = tree.root_id().unwrap();
+        let two_id = tree.get_mut(root_id).unwrap().append(2).node_id();
+        let orphan_id = tree.insert_orphaned(10);
+        let other_id = tree.insert_orphaned(11);
+
+        let mut two_mut = tree.get_mut(two_id).unwrap();
+        assert!(two_mut.insert_before_orphaned(orphan_id).is_some());
+        assert!(two_mut.insert_after_orphaned(other_id).is_some());
+        assert_eq!(child_ids(&tree, root_id), vec![orphan_id, two_id, other_id]);
+
+        let mut two_mut = tree.get_mut(two_id).unwrap();
+        assert!(two_mut.insert_after_orphaned(orphan_id).is_none());
+        assert!(two_mut.insert_after_orphaned(root_id).is_none());
+    }
+
+    #[test]
+    fn insert_orphaned_sibling_into_own_subtree() {
+        let mut tree = Tree::new();
+        tree.set_root(1);
+        let orphan_id = tree.insert_orphaned(10);
+        let mut orphan_mut = tree.get_mut(orphan_id).unwrap();
+        let child_id = orphan_mut.append(11).node_id();
+
+        let mut child_mut = tree.get_mut(child_id).unwrap();
+        assert!(child_mut.insert_before_orphaned(orphan_id).is_none());
+        assert!(child_mut.insert_after_orphaned(orphan_id).is_none());
+        assert!(tree.get(orphan_id).unwrap().is_orphan());
+    }
+
+    #[test]
+    fn graft_tree() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+        let one_id = tree.get_mut(root_id).unwrap().append(1).node_id();
+
+        let mut other = Tree::new();
+        other.set_root(10);
+        let other_root_id = other.root_id().unwrap();
+        let mut other_root = other.root_mut().unwrap();
+        let eleven_id = other_root.append(11).node_id();
+        let twelve_id = other_root.append(12).node_id();
+        let thirteen_id = other.get_mut(eleven_id).unwrap().append(13).node_id();
+        let orphan_id = other.insert_orphaned(14);
+
+        let id_map = tree
+            .get_mut(root_id)
+            .unwrap()
+            .graft(other, Position::First)
+            .unwrap();
+        assert_eq!(id_map.len(), 5);
+
+        let new_root_id = id_map[&other_root_id];
+        assert_eq!(child_ids(&tree, root_id), vec![new_root_id, one_id]);
+        assert_eq!(
+            child_ids(&tree, new_root_id),
+            vec![id_map[&eleven_id], id_map[&twelve_id]]
+        );
+        assert_eq!(
+            child_ids(&tree, id_map[&eleven_id]),
+            vec![id_map[&thirteen_id]]
+        );
+
+        let new_root = tree.get(new_root_id).unwrap();
+        assert_eq!(new_root.data(), &10);
+        assert_eq!(new_root.parent().unwrap().node_id(), root_id);
+        assert_eq!(tree.get(id_map[&thirteen_id]).unwrap().data(), &13);
+        assert!(tree.get(id_map[&orphan_id]).unwrap().is_orphan());
+        assert!(tree.get(thirteen_id).is_none());
+    }
+
+    #[test]
+    fn graft_empty_tree() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let id_map = tree
+            .get_mut(root_id)
+            .unwrap()
+            .graft(Tree::new(), Position::Last)
+            .unwrap();
+        assert!(id_map.is_empty());
+        assert!(tree.root().unwrap().first_child().is_none());
+    }
+
+    #[test]
+    fn graft_bad_position() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut other = Tree::new();
+        let other_root_id = other.set_root(10);
+        let eleven_id = other.root_mut().unwrap().append(11).node_id();
+        let orphan_id = other.insert_orphaned(12);
+
+        let error = tree
+            .get_mut(root_id)
+            .unwrap()
+            .graft(other, Position::Index(1))
+            .unwrap_err();
+        assert_eq!(error.error, MoveError::IndexOutOfBounds(1));
+        assert!(tree.root().unwrap().first_child().is_none());
+
+        // the tree comes back whole, with its NodeIds still valid
+        let other = error.tree;
+        assert_eq!(other.root_id(), Some(other_root_id));
+        assert_eq!(
+            other.get(eleven_id).unwrap().parent().unwrap().node_id(),
+            other_root_id
+        );
+        assert_eq!(other.get(orphan_id).unwrap().data(), &12);
+
+        let sibling_id = tree.get_mut(root_id).unwrap().append(1).node_id();
+        let id_map = tree
+            .get_mut(root_id)
+            .unwrap()
+            .graft(other, Position::After(sibling_id))
+            .unwrap();
+        assert_eq!(
+            child_ids(&tree, root_id),
+            vec![sibling_id, id_map[&other_root_id]]
+        );
+    }
+
+    #[test]
+    fn sort_children_by_is_stable() {
+        let mut tree = Tree::new();
+        tree.set_root((0, 'r'));
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let a = root_mut.append((2, 'a')).node_id();
+        let b = root_mut.append((1, 'b')).node_id();
+        let c = root_mut.append((2, 'c')).node_id();
+        let d = root_mut.append((1, 'd')).node_id();
+
+        root_mut.sort_children_by(|x, y| x.0.cmp(&y.0));
+
+        let children: Vec<NodeId> = tree
+            .get(root_id)
+            .unwrap()
+            .children()
+            .map(|child| child.node_id())
+            .collect();
+        assert_eq!(children, vec![b, d, a, c]);
+
+        let root_node = tree.get_node(root_id).unwrap();
+        assert_eq!(root_node.relatives.first_child, Some(b));
+        assert_eq!(root_node.relatives.last_child, Some(c));
+        assert_eq!(tree.get_node(b).unwrap().relatives.prev_sibling, None);
+        assert_eq!(tree.get_node(c).unwrap().relatives.next_sibling, None);
+        assert_eq!(tree.get_node(a).unwrap().relatives.prev_sibling, Some(d));
+        assert_eq!(tree.get(a).unwrap().data(), &(2, 'a'));
+    }
+
+    #[test]
+    fn sort_children_by_key_reverse() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let one = root_mut.append(1).node_id();
+        let two = root_mut.append(2).node_id();
+        let three = root_mut.append(3).node_id();
+
+        root_mut.sort_children_by_key(|data| std::cmp::Reverse(*data));
+        assert_eq!(child_ids(&tree, root_id), vec![three, two, one]);
+    }
+
+    #[test]
+    fn sort_subtree_by_leaves_other_nodes() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let five = root_mut.append(5).node_id();
+        let four = root_mut.append(4).node_id();
+        let mut five_mut = tree.get_mut(five).unwrap();
+        let seven = five_mut.append(7).node_id();
+        let six = five_mut.append(6).node_id();
+        let mut four_mut = tree.get_mut(four).unwrap();
+        let nine = four_mut.append(9).node_id();
+        let eight = four_mut.append(8).node_id();
+
+        tree.get_mut(five).unwrap().sort_subtree_by(|a, b| a.cmp(b));
+        assert_eq!(child_ids(&tree, five), vec![six, seven]);
+        assert_eq!(child_ids(&tree, four), vec![nine, eight]);
+        assert_eq!(child_ids(&tree, root_id), vec![five, four]);
+
+        tree.get_mut(root_id)
+            .unwrap()
+            .sort_subtree_by(|a, b| a.cmp(b));
+        assert_eq!(child_ids(&tree, root_id), vec![four, five]);
+        assert_eq!(child_ids(&tree, four), vec![eight, nine]);
+    }
+
+    #[test]
+    fn reverse_children_links() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let one = root_mut.append(1).node_id();
+        let two = root_mut.append(2).node_id();
+        let three = root_mut.append(3).node_id();
+
+        root_mut.reverse_children();
+        assert_eq!(child_ids(&tree, root_id), vec![three, two, one]);
+
+        let root_node = tree.get_node(root_id).unwrap();
+        assert_eq!(root_node.relatives.first_child, Some(three));
+        assert_eq!(root_node.relatives.last_child, Some(one));
+        let three_node = tree.get_node(three).unwrap();
+        assert_eq!(three_node.relatives.prev_sibling, None);
+        assert_eq!(three_node.relatives.next_sibling, Some(two));
+        let one_node = tree.get_node(one).unwrap();
+        assert_eq!(one_node.relatives.prev_sibling, Some(two));
+        assert_eq!(one_node.relatives.next_sibling, None);
+    }
+
+    #[test]
+    fn reorder_without_children() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let mut root_mut = tree.root_mut().unwrap();
+
+        root_mut.reverse_children();
+        root_mut.rotate_children_left(3);
+        root_mut.rotate_children_right(3);
+        assert!(root_mut.permute_children(&[]));
+        assert!(root_mut.first_child().is_none());
+        assert!(root_mut.last_child().is_none());
+    }
+
+    #[test]
+    fn rotate_children_wraps() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let one = root_mut.append(1).node_id();
+        let two = root_mut.append(2).node_id();
+        let three = root_mut.append(3).node_id();
+
+        root_mut.rotate_children_left(4);
+        assert_eq!(child_ids(&tree, root_id), vec![two, three, one]);
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        root_mut.rotate_children_right(5);
+        assert_eq!(child_ids(&tree, root_id), vec![three, one, two]);
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        root_mut.rotate_children_right(3);
+        assert_eq!(child_ids(&tree, root_id), vec![three, one, two]);
+    }
+
+    #[test]
+    fn permute_children_rejects_bad_orders() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let one = root_mut.append(1).node_id();
+        let two = root_mut.append(2).node_id();
+        let three = root_mut.append(3).node_id();
+
+        assert!(!root_mut.permute_children(&[0, 1, 3]));
+        assert!(!root_mut.permute_children(&[1, 1, 0]));
+        assert!(!root_mut.permute_children(&[0, 1, 2, 3]));
+        assert_eq!(child_ids(&tree, root_id), vec![one, two, three]);
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        assert!(root_mut.permute_children(&[1, 2, 0]));
+        assert_eq!(child_ids(&tree, root_id), vec![two, three, one]);
+    }
+
+    #[test]
+    fn wrap_middle_child() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let one = root_mut.append(1).node_id();
+        let two = root_mut.append(2).node_id();
+        let three = root_mut.append(3).node_id();
+        let four = tree.get_mut(two).unwrap().append(4).node_id();
+
+        let wrapper = tree.get_mut(two).unwrap().wrap(20).node_id();
+
+        assert_eq!(child_ids(&tree, root_id), vec![one, wrapper, three]);
+        assert_eq!(child_ids(&tree, wrapper), vec![two]);
+        assert_eq!(child_ids(&tree, two), vec![four]);
+
+        let two_node = tree.get_node(two).unwrap();
+        assert_eq!(two_node.relatives.parent, Some(wrapper));
+        assert_eq!(two_node.relatives.prev_sibling, None);
+        assert_eq!(two_node.relatives.next_sibling, None);
+        let wrapper_node = tree.get_node(wrapper).unwrap();
+        assert_eq!(wrapper_node.relatives.parent, Some(root_id));
+        assert_eq!(wrapper_node.relatives.prev_sibling, Some(one));
+        assert_eq!(wrapper_node.relatives.next_sibling, Some(three));
+    }
+
+    #[test]
+    fn wrap_only_child() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+        let one = tree.get_mut(root_id).unwrap().append(1).node_id();
+
+        let wrapper = tree.get_mut(one).unwrap().wrap(10).node_id();
+
+        let root_node = tree.get_node(root_id).unwrap();
+        assert_eq!(root_node.relatives.first_child, Some(wrapper));
+        assert_eq!(root_node.relatives.last_child, Some(wrapper));
+        assert_eq!(child_ids(&tree, wrapper), vec![one]);
+    }
+
+    #[test]
+    fn wrap_root_and_orphan() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+        let orphan_id = tree.insert_orphaned(5);
+
+        let new_root = tree.get_mut(root_id).unwrap().wrap(-1).node_id();
+        assert_eq!(tree.root_id(), Some(new_root));
+        assert_eq!(child_ids(&tree, new_root), vec![root_id]);
+
+        let wrapper = tree.get_mut(orphan_id).unwrap().wrap(50).node_id();
+        assert!(tree.get(wrapper).unwrap().is_orphan());
+        assert_eq!(child_ids(&tree, wrapper), vec![orphan_id]);
+    }
+
+    #[test]
+    fn group_children_ranges() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let ids: Vec<NodeId> = (1..=5).map(|i| root_mut.append(i).node_id()).collect();
+
+        let tail = root_mut.group_children(3.., 45).unwrap().node_id();
+        assert_eq!(
+            child_ids(&tree, root_id),
+            vec![ids[0], ids[1], ids[2], tail]
+        );
+        assert_eq!(child_ids(&tree, tail), vec![ids[3], ids[4]]);
+        assert_eq!(
+            tree.get_node(root_id).unwrap().relatives.last_child,
+            Some(tail)
+        );
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let head = root_mut.group_children(..=1, 12).unwrap().node_id();
+        assert_eq!(child_ids(&tree, root_id), vec![head, ids[2], tail]);
+        assert_eq!(child_ids(&tree, head), vec![ids[0], ids[1]]);
+        assert_eq!(
+            tree.get_node(root_id).unwrap().relatives.first_child,
+            Some(head)
+        );
+        assert_eq!(tree.get_node(ids[1]).unwrap().relatives.next_sibling, None);
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let empty = root_mut.group_children(1..1, 0).unwrap().node_id();
+        assert_eq!(child_ids(&tree, root_id), vec![head, empty, ids[2], tail]);
+        assert!(child_ids(&tree, empty).is_empty());
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let all = root_mut.group_children(.., 99).unwrap().node_id();
+        assert_eq!(child_ids(&tree, root_id), vec![all]);
+        assert_eq!(child_ids(&tree, all), vec![head, empty, ids[2], tail]);
+    }
+
+    #[test]
+    fn group_children_out_of_bounds() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let one = root_mut.append(1).node_id();
+        let two = root_mut.append(2).node_id();
+
+        assert!(root_mut.group_children(1..3, 0).is_none());
+        assert!(root_mut.group_children(3.., 0).is_none());
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = root_mut.group_children(2..1, 0);
+        assert!(reversed.is_none());
+        assert_eq!(child_ids(&tree, root_id), vec![one, two]);
+    }
+
     #[test]
     fn prepend_orphaned_non_orphan_node() {
         let mut tree = Tree::new();
@@ -1627,4 +3238,180 @@ mod node_mut_tests {
 
         assert!(result.is_none());
     }
+
+    #[test]
+    fn extend_children() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let first = root_mut.extend_children(vec![1, 2]);
+        let second = root_mut.extend_children(3..4);
+        assert!(root_mut.extend_children(Vec::new()).is_empty());
+        assert_eq!(
+            child_ids(&tree, root_id),
+            vec![first[0], first[1], second[0]]
+        );
+
+        let root_node = tree.get_node(root_id).unwrap();
+        assert_eq!(root_node.relatives.first_child, Some(first[0]));
+        assert_eq!(root_node.relatives.last_child, Some(second[0]));
+
+        let last = tree.get(second[0]).unwrap();
+        assert_eq!(last.parent().unwrap().node_id(), root_id);
+        assert_eq!(last.prev_sibling().unwrap().node_id(), first[1]);
+        assert!(last.next_sibling().is_none());
+    }
+
+    #[test]
+    fn prepend_children() {
+        let mut tree = Tree::new();
+        tree.set_root(0);
+        let root_id = tree.root_id().unwrap();
+
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        let first = root_mut.prepend_children(vec![3, 4]);
+        let second = root_mut.prepend_children(1..3);
+        assert_eq!(
+            child_ids(&tree, root_id),
+            vec![second[0], second[1], first[0], first[1]]
+        );
+
+        let root_node = tree.get_node(root_id).unwrap();
+        assert_eq!(root_node.relatives.first_child, Some(second[0]));
+        assert_eq!(root_node.relatives.last_child, Some(first[1]));
+
+        let head = tree.get(second[0]).unwrap();
+        assert!(head.prev_sibling().is_none());
+        let three = tree.get(first[0]).unwrap();
+        assert_eq!(three.prev_sibling().unwrap().node_id(), second[1]);
+    }
+
+    #[test]
+    fn extend_without_size_hint() {
+        let mut tree = Tree::new();
+        let root_id = tree.set_root(0);
+        let mut root_mut = tree.get_mut(root_id).unwrap();
+        root_mut.append(1);
+
+        // a filter can't tell how many items it will yield, so nothing is reserved up front
+        root_mut.extend((2..20).filter(|n| n % 5 == 0));
+        let children: Vec<i32> = tree
+            .get(root_id)
+            .unwrap()
+            .children()
+            .map(|child| *child.data())
+            .collect();
+        assert_eq!(children, vec![1, 5, 10, 15]);
+        let last_id = tree
+            .get_node(root_id)
+            .unwrap()
+            .relatives
+            .last_child
+            .unwrap();
+        assert_eq!(tree.get(last_id).unwrap().data(), &15);
+    }
+
+    #[test]
+    fn panicking_iterator_leaves_linked_children() {
+        use std::panic::{AssertUnwindSafe, catch_unwind};
+
+        for prepend in [false, true] {
+            let mut tree = Tree::new();
+            let root_id = tree.set_root(0);
+            let nine_id = tree.get_mut(root_id).unwrap().append(9).node_id();
+
+            let items = (1..5).map(|n| if n == 3 { panic!("no third item") } else { n });
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                let mut root_mut = tree.get_mut(root_id).unwrap();
+                if prepend {
+                    root_mut.prepend_children(items);
+                } else {
+                    root_mut.extend_children(items);
+                }
+            }));
+            assert!(result.is_err());
+
+            let children: Vec<i32> = tree
+                .get(root_id)
+                .unwrap()
+                .children()
+                .map(|child| *child.data())
+                .collect();
+            let expected = if prepend {
+                vec![1, 2, 9]
+            } else {
+                vec![9, 1, 2]
+            };
+            assert_eq!(children, expected);
+            let relatives = tree.get_node(root_id).unwrap().relatives;
+            let ids = child_ids(&tree, root_id);
+            assert_eq!(relatives.first_child, ids.first().copied());
+            assert_eq!(relatives.last_child, ids.last().copied());
+            for window in ids.windows(2) {
+                assert_eq!(
+                    tree.get(window[1])
+                        .unwrap()
+                        .prev_sibling()
+                        .unwrap()
+                        .node_id(),
+                    window[0]
+                );
+            }
+            assert_eq!(tree.orphan_count(), 0);
+            assert_eq!(
+                tree.get(nine_id).unwrap().parent().unwrap().node_id(),
+                root_id
+            );
+        }
+    }
+
+    #[test]
+    fn duplicate_subtree() {
+        let mut tree = Tree::new();
+        let root_id = tree.set_root(0);
+        let mut root = tree.get_mut(root_id).unwrap();
+        assert!(root.duplicate_subtree().is_none());
+        let one_id = {
+            let mut one = root.append(1);
+            one.append(2).append(3);
+            one.node_id()
+        };
+        let four_id = root.append(4).node_id();
+
+        let copy_id = tree
+            .get_mut(one_id)
+            .unwrap()
+            .duplicate_subtree()
+            .unwrap()
+            .node_id();
+        assert_eq!(child_ids(&tree, root_id), vec![one_id, copy_id, four_id]);
+        let data: Vec<i32> = tree
+            .root()
+            .unwrap()
+            .traverse_pre_order()
+            .map(|node| *node.data())
+            .collect();
+        assert_eq!(data, vec![0, 1, 2, 3, 1, 2, 3, 4]);
+
+        let last_id = tree
+            .get_mut(four_id)
+            .unwrap()
+            .duplicate_subtree()
+            .unwrap()
+            .node_id();
+        assert_eq!(
+            tree.root().unwrap().last_child().unwrap().node_id(),
+            last_id
+        );
+
+        let orphan_id = tree.insert_orphaned(5);
+        assert!(
+            tree.get_mut(orphan_id)
+                .unwrap()
+                .duplicate_subtree()
+                .is_none()
+        );
+    }
 }
@@ -74,7 +74,7 @@ impl<'a, T> NodeRef<'a, T> {
     /// assert!(root.parent().is_none());
     /// ```
     ///
-    pub fn parent(&self) -> Option<NodeRef<T>> {
+    pub fn parent(&self) -> Option<NodeRef<'_, T>> {
         self.get_self_as_node()
             .relatives
             .parent
@@ -95,7 +95,7 @@ impl<'a, T> NodeRef<'a, T> {
     /// assert!(root.prev_sibling().is_none());
     /// ```
     ///
-    pub fn prev_sibling(&self) -> Option<NodeRef<T>> {
+    pub fn prev_sibling(&self) -> Option<NodeRef<'_, T>> {
         self.get_self_as_node()
             .relatives
             .prev_sibling
@@ -116,7 +116,7 @@ impl<'a, T> NodeRef<'a, T> {
     /// assert!(root.next_sibling().is_none());
     /// ```
     ///
-    pub fn next_sibling(&self) -> Option<NodeRef<T>> {
+    pub fn next_sibling(&self) -> Option<NodeRef<'_, T>> {
         self.get_self_as_node()
             .relatives
             .next_sibling
@@ -137,7 +137,7 @@ impl<'a, T> NodeRef<'a, T> {
     /// assert!(root.first_child().is_none());
     /// ```
     ///
-    pub fn first_child(&self) -> Option<NodeRef<T>> {
+    pub fn first_child(&self) -> Option<NodeRef<'_, T>> {
         self.get_self_as_node()
             .relatives
             .first_child
@@ -158,7 +158,7 @@ impl<'a, T> NodeRef<'a, T> {
     /// assert!(root.last_child().is_none());
     /// ```
     ///
-    pub fn last_child(&self) -> Option<NodeRef<T>> {
+    pub fn last_child(&self) -> Option<NodeRef<'_, T>> {
         self.get_self_as_node()
             .relatives
             .last_child
@@ -307,6 +307,58 @@ impl<'a, T> NodeRef<'a, T> {
         LevelOrder::new(self, self.tree)
     }
 
+    ///
+    /// Copies the data of this `Node` and its descendants into a new `Tree`, with this `Node` as
+    /// its root.  The new `Tree` is compact, with its `Node`s laid out in pre-order.
+    ///
+    /// ```
+    /// use nary_tree::tree;
+    ///
+    /// tree!(let tree = 0 => [one @ 1 => [2, 3], 4]);
+    ///
+    /// let subtree = tree.get(one).unwrap().clone_subtree();
+    ///
+    /// let mut s = String::new();
+    /// subtree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 1
+    /// ├── 2
+    /// └── 3
+    /// ");
+    /// ```
+    ///
+    pub fn clone_subtree(&self) -> Tree<T>
+    where
+        T: Clone,
+    {
+        self.tree.clone_subtree_from(self.node_id)
+    }
+
+    ///
+    /// Returns `true` if the sub-tree rooted at this `Node` has the same shape and holds equal data
+    /// as the one rooted at `other`, which may belong to a different `Tree`.  `NodeId`s are
+    /// ignored.
+    ///
+    /// ```
+    /// use nary_tree::tree;
+    ///
+    /// tree!(let tree = 0 => [a @ 1 => [2, 3], b @ 1 => [2, 3], c @ 1 => [2]]);
+    /// let a = tree.get(a).unwrap();
+    ///
+    /// assert!(a.structurally_eq(&tree.get(b).unwrap()));
+    /// assert!(!a.structurally_eq(&tree.get(c).unwrap()));
+    /// assert!(a.structurally_eq(&tree!(1 => [2, 3]).root().unwrap()));
+    /// ```
+    ///
+    pub fn structurally_eq(&self, other: &NodeRef<'_, T>) -> bool
+    where
+        T: PartialEq,
+    {
+        self.tree
+            .subtree_shape(self.node_id)
+            .eq(other.tree.subtree_shape(other.node_id))
+    }
+
     fn get_self_as_node(&self) -> &Node<T> {
         if let Some(node) = self.tree.get_node(self.node_id) {
             node
@@ -409,4 +461,53 @@ mod node_ref_tests {
             assert_eq!(node_ref.data(), &values[i]);
         }
     }
+
+    #[test]
+    fn clone_subtree() {
+        let mut tree = Tree::new();
+        let root_id = tree.set_root(0);
+        let mut root = tree.get_mut(root_id).unwrap();
+        let one_id = {
+            let mut one = root.append(1);
+            one.append(2).append(3);
+            one.append(4);
+            one.node_id()
+        };
+        root.append(5);
+        tree.get_mut(one_id)
+            .unwrap()
+            .remove_first(crate::behaviors::RemoveBehavior::DropChildren);
+
+        let subtree = tree.get(one_id).unwrap().clone_subtree();
+        let data: Vec<i32> = subtree
+            .core_tree
+            .iter()
+            .map(|(_, node)| node.data)
+            .collect();
+        assert_eq!(data, vec![1, 4]);
+        assert!(subtree.root().unwrap().parent().is_none());
+        assert!(subtree.orphans().next().is_none());
+
+        let leaf = tree.root().unwrap().last_child().unwrap().clone_subtree();
+        assert_eq!(leaf.root().unwrap().data(), &5);
+        assert!(leaf.root().unwrap().next_sibling().is_none());
+    }
+
+    #[test]
+    fn structurally_eq() {
+        let mut tree = Tree::new();
+        let root_id = tree.set_root(0);
+        let mut root = tree.get_mut(root_id).unwrap();
+        let a_id = root.append(1).node_id();
+        let b_id = root.append(1).node_id();
+        tree.get_mut(a_id).unwrap().append(2);
+        let orphan_id = tree.insert_orphaned(1);
+
+        let a = tree.get(a_id).unwrap();
+        let b = tree.get(b_id).unwrap();
+        assert!(!a.structurally_eq(&b));
+        assert!(b.structurally_eq(&tree.get(orphan_id).unwrap()));
+        assert!(a.structurally_eq(&a));
+        assert!(!tree.root().unwrap().structurally_eq(&a));
+    }
 }
@@ -0,0 +1,418 @@
+use std::str::FromStr;
+
+use crate::NodeId;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::tree::Tree;
+
+const BRANCH: &str = "├── ";
+const LAST_BRANCH: &str = "└── ";
+const GUIDE: &str = "│   ";
+const BLANK: &str = "    ";
+
+impl<T: FromStr> Tree<T> {
+    ///
+    /// Parses a `Tree` written by `write_formatted` (or `Display`), parsing the data of each
+    /// `Node` with `FromStr`.  Data spanning several lines is joined back together with `\n`.  An
+    /// empty string gives an empty `Tree`.
+    ///
+    /// Returns a `ParseError` with the line and column at fault if the box-drawing prefixes don't
+    /// describe a tree or if the data of a `Node` can't be parsed.
+    ///
+    /// `write_formatted` doesn't escape the data it writes, so a line of data after the first that
+    /// starts with `├── ` or `└── ` reads as a new child of the `Node`.  Such a `Tree` doesn't
+    /// survive the round trip, and no error tells it apart.
+    ///
+    /// ```
+    /// use nary_tree::tree::Tree;
+    /// use nary_tree::error::ParseErrorKind;
+    ///
+    /// let text = "\
+    /// 0
+    /// ├── 1
+    /// │   └── 2
+    /// └── 3
+    /// ";
+    /// let tree: Tree<i32> = Tree::parse_formatted(text).unwrap();
+    /// assert_eq!(tree.root().unwrap().last_child().unwrap().data(), &3);
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(s, text);
+    ///
+    /// let error = Tree::<i32>::parse_formatted("0\n└── x\n").unwrap_err();
+    /// assert_eq!((error.line, error.column), (2, 5));
+    /// assert!(matches!(error.kind, ParseErrorKind::Data(_)));
+    /// ```
+    ///
+    pub fn parse_formatted(s: &str) -> Result<Tree<T>, ParseError<T::Err>> {
+        let mut builder = Builder::new();
+        let mut line_count = 0;
+        for (i, line) in s.lines().enumerate() {
+            let line_number = i + 1;
+            line_count = line_number;
+            if builder.levels.is_empty() {
+                builder.start_node(0, Some(true), line_number, 1, line)?;
+                continue;
+            }
+
+            // only the guides of the current node's ancestors can come before a node or a line of
+            // data, so they are matched up to the first one that differs
+            let depth = builder.levels.len() - 1;
+            let mut rest = line;
+            let mut matched = 0;
+            while matched < depth {
+                let guide = if builder.levels[matched + 1].last == Some(true) {
+                    BLANK
+                } else {
+                    GUIDE
+                };
+                match rest.strip_prefix(guide) {
+                    Some(after_guide) => {
+                        rest = after_guide;
+                        matched += 1;
+                    }
+                    None => break,
+                }
+            }
+
+            let column = matched * 4 + 1;
+            let branch = rest
+                .strip_prefix(BRANCH)
+                .map(|data| (data, false))
+                .or_else(|| rest.strip_prefix(LAST_BRANCH).map(|data| (data, true)));
+            match branch {
+                Some((data, last)) => {
+                    builder.start_node(matched + 1, Some(last), line_number, column + 4, data)?
+                }
+                None if matched == depth => builder.push_data_line(rest),
+                None => {
+                    return Err(ParseError::new(
+                        line_number,
+                        column,
+                        ParseErrorKind::InvalidPrefix,
+                    ));
+                }
+            }
+        }
+        builder.finish(line_count + 1)
+    }
+
+    ///
+    /// Parses a `Tree` from a plain outline, where each line holds the data of one `Node` and
+    /// children are indented further than their parent.  Siblings must be indented the same way;
+    /// blank lines are skipped.  The data of each `Node` (without the surrounding whitespace) is
+    /// parsed with `FromStr`.  An empty string gives an empty `Tree`.
+    ///
+    /// Returns a `ParseError` with the line and column at fault if the indentation doesn't
+    /// describe a tree with a single root or if the data of a `Node` can't be parsed.
+    ///
+    /// ```
+    /// use nary_tree::tree::Tree;
+    /// use nary_tree::error::ParseErrorKind;
+    ///
+    /// let text = "
+    /// 0
+    ///   1
+    ///     2
+    ///   3
+    /// ";
+    /// let tree: Tree<i32> = Tree::parse_outline(text).unwrap();
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 0
+    /// ├── 1
+    /// │   └── 2
+    /// └── 3
+    /// ");
+    ///
+    /// let error = Tree::<i32>::parse_outline("0\n    1\n  2\n").unwrap_err();
+    /// assert_eq!((error.line, error.column), (3, 3));
+    /// assert_eq!(error.kind, ParseErrorKind::InvalidIndent);
+    /// ```
+    ///
+    pub fn parse_outline(s: &str) -> Result<Tree<T>, ParseError<T::Err>> {
+        let mut builder = Builder::new();
+        let mut indents: Vec<usize> = Vec::new();
+        let mut line_count = 0;
+        for (i, line) in s.lines().enumerate() {
+            let line_number = i + 1;
+            line_count = line_number;
+            let data = line.trim_start();
+            if data.is_empty() {
+                continue;
+            }
+
+            let indent = line.chars().count() - data.chars().count();
+            let column = indent + 1;
+            let level = match indents.iter().position(|&ancestor| ancestor >= indent) {
+                None => indents.len(),
+                Some(0) if indents[0] == indent => {
+                    return Err(ParseError::new(
+                        line_number,
+                        column,
+                        ParseErrorKind::MultipleRoots,
+                    ));
+                }
+                Some(level) if indents[level] == indent => level,
+                Some(_) => {
+                    return Err(ParseError::new(
+                        line_number,
+                        column,
+                        ParseErrorKind::InvalidIndent,
+                    ));
+                }
+            };
+            indents.truncate(level);
+            indents.push(indent);
+            builder.start_node(level, None, line_number, column, data.trim_end())?;
+        }
+        builder.finish(line_count + 1)
+    }
+}
+
+/// A `Node` on the path from the root to the last `Node` read.
+struct Level {
+    node_id: Option<NodeId>,
+    last: Option<bool>,
+}
+
+/// The data of the last `Node` read, which can't be parsed until all its lines have been read.
+struct PendingData {
+    line: usize,
+    column: usize,
+    lines: Vec<String>,
+}
+
+/// Builds a `Tree` from `Node`s given in pre-order along with their depth.
+struct Builder<T> {
+    tree: Tree<T>,
+    levels: Vec<Level>,
+    pending: Option<PendingData>,
+}
+
+impl<T: FromStr> Builder<T> {
+    fn new() -> Builder<T> {
+        Builder {
+            tree: Tree::new(),
+            levels: Vec::new(),
+            pending: None,
+        }
+    }
+
+    /// Starts a new `Node` at depth `level`, which must be at most one more than the depth of the
+    /// last `Node`.  `last` tells whether the `Node` was drawn as the last of its siblings, if
+    /// the format says so.
+    fn start_node(
+        &mut self,
+        level: usize,
+        last: Option<bool>,
+        line: usize,
+        column: usize,
+        data: &str,
+    ) -> Result<(), ParseError<T::Err>> {
+        self.flush()?;
+
+        if self
+            .levels
+            .get(level)
+            .is_some_and(|prev_sibling| prev_sibling.last == Some(true))
+        {
+            return Err(ParseError::new(
+                line,
+                column,
+                ParseErrorKind::SiblingAfterLast,
+            ));
+        }
+        self.check_closed(level + 1, line, column)?;
+
+        self.levels.truncate(level);
+        self.levels.push(Level {
+            node_id: None,
+            last,
+        });
+        self.pending = Some(PendingData {
+            line,
+            column,
+            lines: vec![data.to_string()],
+        });
+        Ok(())
+    }
+
+    fn push_data_line(&mut self, data: &str) {
+        if let Some(pending) = self.pending.as_mut() {
+            pending.lines.push(data.to_string());
+        }
+    }
+
+    fn finish(mut self, end_line: usize) -> Result<Tree<T>, ParseError<T::Err>> {
+        self.flush()?;
+        self.check_closed(1, end_line, 1)?;
+        Ok(self.tree)
+    }
+
+    /// Checks that the `Node`s from depth `level` down, which are about to be left behind, were
+    /// all drawn as the last of their siblings.
+    fn check_closed(
+        &self,
+        level: usize,
+        line: usize,
+        column: usize,
+    ) -> Result<(), ParseError<T::Err>> {
+        let levels = self.levels.get(level..).unwrap_or_default();
+        if levels.iter().any(|closed| closed.last == Some(false)) {
+            return Err(ParseError::new(
+                line,
+                column,
+                ParseErrorKind::MissingSibling,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parses the data of the last `Node` read and adds the `Node` to the `Tree`.
+    fn flush(&mut self) -> Result<(), ParseError<T::Err>> {
+        let pending = match self.pending.take() {
+            Some(pending) => pending,
+            None => return Ok(()),
+        };
+        let data = T::from_str(&pending.lines.join("\n")).map_err(|error| {
+            ParseError::new(pending.line, pending.column, ParseErrorKind::Data(error))
+        })?;
+
+        let depth = self.levels.len() - 1;
+        let node_id = if depth == 0 {
+            self.tree.set_root(data)
+        } else {
+            let parent_id = self.levels[depth - 1]
+                .node_id
+                .expect("parent is added before its children");
+            self.tree
+                .get_mut(parent_id)
+                .expect("parent must exist")
+                .append(data)
+                .node_id()
+        };
+        self.levels[depth].node_id = Some(node_id);
+        Ok(())
+    }
+}
+
+#[cfg_attr(tarpaulin, skip)]
+#[cfg(test)]
+mod parse_tests {
+    use crate::error::ParseErrorKind;
+    use crate::tree::{Tree, TreeBuilder};
+
+    fn formatted<T: std::fmt::Display>(tree: &Tree<T>) -> String {
+        let mut s = String::new();
+        tree.write_formatted(&mut s).unwrap();
+        s
+    }
+
+    #[test]
+    fn round_trip_multiline() {
+        let mut tree = TreeBuilder::new()
+            .with_root(String::from("root\nof the tree"))
+            .build();
+        let mut root = tree.root_mut().unwrap();
+        {
+            let mut one = root.append(String::from("one\n  indented"));
+            one.append(String::from("two\n\nafter a blank line"));
+            one.append(String::from("├── not a node"));
+        }
+        root.append(String::from("three\nlast"))
+            .append(String::from("four"));
+
+        let text = formatted(&tree);
+        let parsed: Tree<String> = Tree::parse_formatted(&text).unwrap();
+        assert_eq!(formatted(&parsed), text);
+
+        let root = parsed.root().unwrap();
+        assert_eq!(root.data(), "root\nof the tree");
+        assert_eq!(
+            root.first_child().unwrap().first_child().unwrap().data(),
+            "two\n\nafter a blank line"
+        );
+        assert_eq!(root.last_child().unwrap().data(), "three\nlast");
+    }
+
+    #[test]
+    fn continuation_line_looking_like_a_branch() {
+        let mut tree = TreeBuilder::new().with_root(String::from("0")).build();
+        tree.root_mut()
+            .unwrap()
+            .append(String::from("1\n├── 2\n└── 3"));
+
+        let text = formatted(&tree);
+        assert_eq!(text, "0\n└── 1\n    ├── 2\n    └── 3\n");
+        let parsed: Tree<String> = Tree::parse_formatted(&text).unwrap();
+        assert_eq!(formatted(&parsed), text);
+
+        let root = parsed.root().unwrap();
+        let child = root.first_child().unwrap();
+        assert_eq!(child.data(), "1");
+        let grandchildren: Vec<&str> = child.children().map(|node| node.data().as_str()).collect();
+        assert_eq!(grandchildren, vec!["2", "3"]);
+    }
+
+    #[test]
+    fn parse_formatted_empty() {
+        let tree: Tree<i32> = Tree::parse_formatted("").unwrap();
+        assert!(tree.root().is_none());
+    }
+
+    #[test]
+    fn parse_formatted_errors() {
+        let error = Tree::<i32>::parse_formatted("0\n└── 1\n│   ├── 2\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.kind),
+            (3, 1, ParseErrorKind::InvalidPrefix)
+        );
+
+        let error = Tree::<i32>::parse_formatted("0\n└── 1\n├── 2\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.kind),
+            (3, 5, ParseErrorKind::SiblingAfterLast)
+        );
+
+        let error = Tree::<i32>::parse_formatted("0\n├── 1\n│   ├── 2\n└── 3\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.kind),
+            (4, 5, ParseErrorKind::MissingSibling)
+        );
+
+        let error = Tree::<i32>::parse_formatted("0\n├── 1\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.kind),
+            (3, 1, ParseErrorKind::MissingSibling)
+        );
+    }
+
+    #[test]
+    fn parse_outline_siblings_and_blank_lines() {
+        let text = "a\n\tb\n\t\tc\n\n\td\n\t\te\n";
+        let tree: Tree<String> = Tree::parse_outline(text).unwrap();
+        assert_eq!(formatted(&tree), "a\n├── b\n│   └── c\n└── d\n    └── e\n");
+    }
+
+    #[test]
+    fn parse_outline_errors() {
+        let error = Tree::<i32>::parse_outline("  0\n    1\n  2\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.kind),
+            (3, 3, ParseErrorKind::MultipleRoots)
+        );
+
+        let error = Tree::<i32>::parse_outline("  0\n1\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.kind),
+            (2, 1, ParseErrorKind::InvalidIndent)
+        );
+
+        let error = Tree::<i32>::parse_outline("0\n  1\n  one\n").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 3));
+        assert!(matches!(error.kind, ParseErrorKind::Data(_)));
+    }
+}
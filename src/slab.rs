@@ -22,6 +22,20 @@ impl<T> SlabNode<T> {
     }
 }
 
+pub(crate) struct Iter<'a, T> {
+    inner: slab_tokio::Iter<'a, SlabNode<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(index, node)| (Index::new(index, node.generation), &node.data))
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct Slab<T> {
     slab: slab_tokio::Slab<SlabNode<T>>,
@@ -48,10 +62,10 @@ impl<T> Slab<T> {
     }
 
     pub(crate) fn try_remove(&mut self, index: Index) -> Option<T> {
-        if let Some(to_remove) = self.slab.get(index.index) {
-            if to_remove.generation != index.generation {
-                return None;
-            }
+        if let Some(to_remove) = self.slab.get(index.index)
+            && to_remove.generation != index.generation
+        {
+            return None;
         }
         self.slab.try_remove(index.index).map(|entry| {
             self.next_generation();
@@ -81,10 +95,30 @@ impl<T> Slab<T> {
         }
     }
 
+    pub(crate) fn len(&self) -> usize {
+        self.slab.len()
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.slab.iter(),
+        }
+    }
+
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.slab.reserve(additional);
+    }
+
     pub(crate) fn shrink_to_fit(&mut self) {
         self.slab.shrink_to_fit();
     }
 
+    pub(crate) fn into_entries(self) -> impl Iterator<Item = (Index, T)> {
+        self.slab
+            .into_iter()
+            .map(|(index, node)| (Index::new(index, node.generation), node.data))
+    }
+
     #[cfg(feature = "experimental")]
     pub(crate) fn compact<F>(&mut self, mut rekey: F)
     where
@@ -299,6 +333,34 @@ mod tests {
         assert!(eight_rem.is_none());
     }
 
+    #[test]
+    fn iter() {
+        let mut slab = Slab::new(5);
+        let six = slab.insert(6);
+        let seven = slab.insert(7);
+        let eight = slab.insert(8);
+        slab.try_remove(seven);
+
+        let entries: Vec<_> = slab.iter().collect();
+        assert_eq!(entries, vec![(six, &6), (eight, &8)]);
+        assert_eq!(slab.len(), 2);
+    }
+
+    #[test]
+    fn into_entries() {
+        let mut slab = Slab::new(5);
+        let six = slab.insert(6);
+        let seven = slab.insert(7);
+        let eight = slab.insert(8);
+        slab.try_remove(seven);
+        let nine = slab.insert(9);
+        // |6|9|8|
+
+        let entries: Vec<_> = slab.into_entries().collect();
+        assert_eq!(entries, vec![(six, 6), (nine, 9), (eight, 8)]);
+        assert_eq!(nine.generation, 1);
+    }
+
     #[test]
     fn get() {
         let mut slab = Slab::new(5);
@@ -0,0 +1,63 @@
+//! Helpers shared by the tests of several modules.
+
+use crate::NodeId;
+use crate::behaviors::{Position, RemoveBehavior};
+use crate::tree::Tree;
+
+/// A small deterministic generator, so that failures can be reproduced.
+pub(crate) struct Rng(pub(crate) u64);
+
+impl Rng {
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+/// Builds a `Tree` of `size` `Node`s, each holding a value below 5 and hung under a random
+/// earlier `Node`.
+pub(crate) fn random_tree(rng: &mut Rng, size: usize) -> Tree<i32> {
+    let mut tree = Tree::new();
+    let mut ids = vec![tree.set_root(rng.below(5) as i32)];
+    for _ in 1..size {
+        let parent_id = ids[rng.below(ids.len())];
+        let data = rng.below(5) as i32;
+        ids.push(tree.get_mut(parent_id).unwrap().append(data).node_id());
+    }
+    tree
+}
+
+/// Makes `steps` random changes to the rooted `Tree`: appends, removals, updates and moves.
+pub(crate) fn mutate(rng: &mut Rng, tree: &mut Tree<i32>, steps: usize) {
+    for _ in 0..steps {
+        let ids: Vec<NodeId> = tree
+            .root()
+            .unwrap()
+            .traverse_pre_order()
+            .map(|node| node.node_id())
+            .collect();
+        let node_id = ids[rng.below(ids.len())];
+        match rng.below(4) {
+            0 => {
+                tree.get_mut(node_id).unwrap().append(rng.below(9) as i32);
+            }
+            1 if Some(node_id) != tree.root_id() => {
+                tree.remove(node_id, RemoveBehavior::DropChildren);
+            }
+            2 => {
+                *tree.get_mut(node_id).unwrap().data() = rng.below(9) as i32;
+            }
+            _ => {
+                let parent_id = ids[rng.below(ids.len())];
+                let count = tree.get(parent_id).unwrap().children().count();
+                let index = rng.below(count + 1);
+                let _ = tree
+                    .get_mut(node_id)
+                    .unwrap()
+                    .move_to(parent_id, Position::Index(index));
+            }
+        }
+    }
+}
@@ -1,9 +1,17 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::Infallible;
 use std::fmt::Display;
 use std::fmt::Write;
+use std::hash::Hash;
 
+use crate::IdMap;
+use crate::KeyMap;
 use crate::NodeId;
 use crate::behaviors::*;
 use crate::core_tree::CoreTree;
+use crate::error::{BuildError, MoveError, RemoveError};
+use crate::iter::{DrainSubtree, IntoIter, Orphans};
 use crate::node::*;
 
 ///
@@ -168,6 +176,177 @@ impl<T> Tree<T> {
         self.core_tree.insert(data)
     }
 
+    ///
+    /// Builds a `Tree` from a list of `Node`s, each given as its key, the key of its parent (or
+    /// `None` for the root) and its data.  Children keep the order in which they appear in the
+    /// list.  Returns the `Tree` along with a map from each key to the `NodeId` of its `Node`.
+    ///
+    /// Fails with a `BuildError` naming the key at fault if a key is used twice, if a parent key
+    /// doesn't exist, if the parent links form a cycle or if there isn't exactly one root.  A
+    /// cycle is reported on the first `Node` of the list that is part of one, even when there is
+    /// no root.
+    ///
+    /// ```
+    /// use nary_tree::tree::Tree;
+    /// use nary_tree::error::BuildError;
+    ///
+    /// let rows = vec![
+    ///     ("b", Some("a"), 2),
+    ///     ("a", None, 1),
+    ///     ("c", Some("b"), 3),
+    ///     ("d", Some("a"), 4),
+    /// ];
+    /// let (tree, ids) = Tree::try_from_parents(rows).unwrap();
+    /// assert_eq!(tree.get(ids["c"]).unwrap().parent().unwrap().data(), &2);
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 1
+    /// ├── 2
+    /// │   └── 3
+    /// └── 4
+    /// ");
+    ///
+    /// let rows = vec![("a", None, 1), ("b", Some("c"), 2)];
+    /// assert_eq!(
+    ///     Tree::try_from_parents(rows).unwrap_err(),
+    ///     BuildError::DanglingParent { node: "b", parent: "c" });
+    /// ```
+    ///
+    pub fn try_from_parents<K, I>(nodes: I) -> Result<(Tree<T>, KeyMap<K>), BuildError<K>>
+    where
+        K: Eq + Hash + Clone,
+        I: IntoIterator<Item = (K, Option<K>, T)>,
+    {
+        let mut keys = Vec::new();
+        let mut parents = Vec::new();
+        let mut data = Vec::new();
+        let mut indexes: HashMap<K, usize> = HashMap::new();
+        for (key, parent, value) in nodes {
+            if indexes.insert(key.clone(), keys.len()).is_some() {
+                return Err(BuildError::DuplicateKey(key));
+            }
+            keys.push(key);
+            parents.push(parent);
+            data.push(Some(value));
+        }
+
+        let mut root = None;
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); keys.len()];
+        for (i, parent) in parents.iter().enumerate() {
+            match parent {
+                None if root.is_some() => return Err(BuildError::MultipleRoots(keys[i].clone())),
+                None => root = Some(i),
+                Some(parent) => match indexes.get(parent) {
+                    Some(&parent_index) => children[parent_index].push(i),
+                    None => {
+                        return Err(BuildError::DanglingParent {
+                            node: keys[i].clone(),
+                            parent: parent.clone(),
+                        });
+                    }
+                },
+            }
+        }
+        if let Some(i) = first_in_cycle(&parents, &indexes) {
+            return Err(BuildError::Cycle(keys[i].clone()));
+        }
+        // without cycles, following the parents of any node ends up at the root
+        let root = root.ok_or(BuildError::NoRoot)?;
+
+        let mut tree = TreeBuilder::new().with_capacity(keys.len()).build();
+        let mut node_ids: Vec<Option<NodeId>> = vec![None; keys.len()];
+        node_ids[root] = Some(tree.set_root(data[root].take().expect("root is built once")));
+        let mut stack = vec![root];
+        while let Some(i) = stack.pop() {
+            let parent_id = node_ids[i].expect("parent is built before its children");
+            for &child in &children[i] {
+                let value = data[child].take().expect("each node is built once");
+                node_ids[child] = Some(tree.new_node_mut(parent_id).append(value).node_id());
+                stack.push(child);
+            }
+        }
+
+        let id_map = keys
+            .into_iter()
+            .zip(node_ids)
+            .map(|(key, node_id)| (key, node_id.expect("every node was built")))
+            .collect();
+        Ok((tree, id_map))
+    }
+
+    ///
+    /// Builds a `Tree` from a list of keyed `Node`s and a list of `(parent, child)` edges between
+    /// their keys.  Children keep the order of their edges.  Returns the `Tree` along with a map
+    /// from each key to the `NodeId` of its `Node`.
+    ///
+    /// Fails with a `BuildError` naming the key at fault for the same reasons as
+    /// `try_from_parents`, or if an edge names a child that doesn't exist or a child has more than
+    /// one edge.
+    ///
+    /// ```
+    /// use nary_tree::tree::Tree;
+    /// use nary_tree::error::BuildError;
+    ///
+    /// let nodes = vec![(1, "one"), (2, "two"), (3, "three")];
+    /// let (tree, ids) = Tree::try_from_edges(nodes, vec![(1, 3), (1, 2)]).unwrap();
+    ///
+    /// let root = tree.root().unwrap();
+    /// assert_eq!(root.node_id(), ids[&1]);
+    /// assert_eq!(
+    ///     root.children().map(|child| *child.data()).collect::<Vec<&str>>(),
+    ///     vec!["three", "two"]);
+    ///
+    /// let nodes = vec![(1, "one"), (2, "two"), (3, "three")];
+    /// assert_eq!(
+    ///     Tree::try_from_edges(nodes, vec![(1, 2), (3, 2)]).unwrap_err(),
+    ///     BuildError::MultipleParents(2));
+    /// ```
+    ///
+    pub fn try_from_edges<K, N, E>(
+        nodes: N,
+        edges: E,
+    ) -> Result<(Tree<T>, KeyMap<K>), BuildError<K>>
+    where
+        K: Eq + Hash + Clone,
+        N: IntoIterator<Item = (K, T)>,
+        E: IntoIterator<Item = (K, K)>,
+    {
+        let mut keys = Vec::new();
+        let mut known = HashSet::new();
+        let mut data = HashMap::new();
+        for (key, value) in nodes {
+            if !known.insert(key.clone()) {
+                return Err(BuildError::DuplicateKey(key));
+            }
+            keys.push(key.clone());
+            data.insert(key, value);
+        }
+
+        let mut child_rows = Vec::new();
+        for (parent, child) in edges {
+            let value = match data.remove(&child) {
+                Some(value) => value,
+                None if known.contains(&child) => return Err(BuildError::MultipleParents(child)),
+                None => return Err(BuildError::UnknownChild(child)),
+            };
+            if !known.contains(&parent) {
+                return Err(BuildError::DanglingParent {
+                    node: child,
+                    parent,
+                });
+            }
+            child_rows.push((child, Some(parent), value));
+        }
+
+        let root_rows: Vec<(K, Option<K>, T)> = keys
+            .into_iter()
+            .filter_map(|key| data.remove(&key).map(|value| (key, None, value)))
+            .collect();
+        Tree::try_from_parents(root_rows.into_iter().chain(child_rows))
+    }
+
     ///
     /// Returns the `Tree`'s current capacity.  Capacity is defined as the number of times new
     /// `Node`s can be added to the `Tree` before it must allocate more memory.
@@ -216,7 +395,7 @@ impl<T> Tree<T> {
     /// assert_eq!(root.data(), &1);
     /// ```
     ///
-    pub fn root(&self) -> Option<NodeRef<T>> {
+    pub fn root(&self) -> Option<NodeRef<'_, T>> {
         self.root_id.map(|id| self.new_node_ref(id))
     }
 
@@ -236,7 +415,7 @@ impl<T> Tree<T> {
     /// assert_eq!(root.data(), &mut 2);
     /// ```
     ///
-    pub fn root_mut(&mut self) -> Option<NodeMut<T>> {
+    pub fn root_mut(&mut self) -> Option<NodeMut<'_, T>> {
         self.root_id.map(move |id| self.new_node_mut(id))
     }
 
@@ -259,7 +438,7 @@ impl<T> Tree<T> {
     /// assert_eq!(root.data(), &1);
     /// ```
     ///
-    pub fn get(&self, node_id: NodeId) -> Option<NodeRef<T>> {
+    pub fn get(&self, node_id: NodeId) -> Option<NodeRef<'_, T>> {
         let _ = self.core_tree.get(node_id)?;
         Some(self.new_node_ref(node_id))
     }
@@ -285,7 +464,7 @@ impl<T> Tree<T> {
     /// assert_eq!(root.data(), &mut 2);
     /// ```
     ///
-    pub fn get_mut(&mut self, node_id: NodeId) -> Option<NodeMut<T>> {
+    pub fn get_mut(&mut self, node_id: NodeId) -> Option<NodeMut<'_, T>> {
         let _ = self.core_tree.get_mut(node_id)?;
         Some(self.new_node_mut(node_id))
     }
@@ -294,8 +473,11 @@ impl<T> Tree<T> {
     /// Remove a `Node` by its `NodeId` and return the data that it contained.
     /// Returns a `Some`-value if the `Node` exists; returns a `None`-value otherwise.
     ///
-    /// Children of the removed `Node` can either be dropped with `DropChildren` or orphaned with
-    /// `OrphanChildren`.
+    /// Children of the removed `Node` can either be dropped with `DropChildren`, orphaned with
+    /// `OrphanChildren`, moved into the removed `Node`'s place with `PromoteChildren` or appended
+    /// to another `Node` with `ReparentChildrenTo`.  If the children can't be moved as requested
+    /// (see `RemoveBehavior`) nothing is removed and a `None`-value is returned; `try_remove`
+    /// says why.
     ///
     /// ```
     /// use nary_tree::tree::TreeBuilder;
@@ -323,42 +505,628 @@ impl<T> Tree<T> {
     /// ```
     ///
     pub fn remove(&mut self, node_id: NodeId, behavior: RemoveBehavior) -> Option<T> {
-        if let Some(node) = self.get_node(node_id) {
-            let Relatives {
-                parent,
-                prev_sibling,
-                next_sibling,
-                ..
-            } = node.relatives;
-
-            let (is_first_child, is_last_child) = self.is_node_first_last_child(node_id);
-
-            if is_first_child {
-                // parent first child = my next sibling
-                self.set_first_child(parent.expect("parent must exist"), next_sibling);
+        self.try_remove(node_id, behavior).ok()
+    }
+
+    ///
+    /// Remove a `Node` by its `NodeId` and return the data that it contained, the way `remove`
+    /// does, but say why nothing was removed: `RemoveError::NodeNotFound` if the `Node` doesn't
+    /// exist, or the reason its children couldn't be moved as requested.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    /// use nary_tree::behaviors::RemoveBehavior::*;
+    /// use nary_tree::error::RemoveError;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(1).build();
+    /// let root_id = tree.root_id().expect("root doesn't exist?");
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// let two_id = root.append(2).node_id();
+    /// root.append(3);
+    ///
+    /// assert_eq!(tree.try_remove(root_id, PromoteChildren), Err(RemoveError::RootHasManyChildren));
+    /// assert_eq!(tree.try_remove(two_id, PromoteChildren), Ok(2));
+    /// assert_eq!(tree.try_remove(two_id, PromoteChildren), Err(RemoveError::NodeNotFound));
+    /// ```
+    ///
+    pub fn try_remove(
+        &mut self,
+        node_id: NodeId,
+        behavior: RemoveBehavior,
+    ) -> Result<T, RemoveError> {
+        if self.get_node(node_id).is_none() {
+            return Err(RemoveError::NodeNotFound);
+        }
+        match behavior {
+            RemoveBehavior::PromoteChildren => self.promote_children(node_id)?,
+            RemoveBehavior::ReparentChildrenTo(parent_id) => {
+                self.reparent_children(node_id, parent_id)?
             }
-            if is_last_child {
-                // parent last child = my prev sibling
-                self.set_last_child(parent.expect("parent must exist"), prev_sibling);
+            RemoveBehavior::DropChildren | RemoveBehavior::OrphanChildren => {}
+        }
+
+        if self.root_id == Some(node_id) {
+            self.root_id = None;
+        }
+        self.unlink(node_id);
+
+        match behavior {
+            RemoveBehavior::DropChildren => self.drop_children(node_id),
+            RemoveBehavior::OrphanChildren => self.orphan_children(node_id),
+            RemoveBehavior::PromoteChildren | RemoveBehavior::ReparentChildrenTo(_) => {}
+        };
+        Ok(self
+            .core_tree
+            .remove(node_id)
+            .expect("node was checked to exist"))
+    }
+
+    ///
+    /// Removes the sub-tree rooted at the `Node` identified by `node_id` from this `Tree` and
+    /// returns it as a new, independent `Tree` (with its own tree id) along with a map from the
+    /// `NodeId`s the `Node`s had in this `Tree` to their `NodeId`s in the new one.  Child order is
+    /// preserved.  Returns a `None`-value if the `Node` doesn't exist.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let (one_id, two_id) = {
+    ///     let mut root = tree.root_mut().expect("root doesn't exist?");
+    ///     let mut one = root.append(1);
+    ///     let two_id = one.append(2).node_id();
+    ///     one.append(3);
+    ///     (one.node_id(), two_id)
+    /// };
+    /// tree.root_mut().unwrap().append(4);
+    ///
+    /// let (split, id_map) = tree.split_off(one_id).unwrap();
+    /// assert!(tree.get(two_id).is_none());
+    /// assert_eq!(split.get(id_map[&two_id]).unwrap().data(), &2);
+    ///
+    /// let mut s = String::new();
+    /// split.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 1
+    /// ├── 2
+    /// └── 3
+    /// ");
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 0
+    /// └── 4
+    /// ");
+    /// ```
+    ///
+    pub fn split_off(&mut self, node_id: NodeId) -> Option<(Tree<T>, IdMap)> {
+        let sub_tree: Vec<(NodeId, Option<NodeId>)> = self
+            .get(node_id)?
+            .traverse_pre_order()
+            .map(|node_ref| (node_ref.node_id(), node_ref.parent().map(|p| p.node_id())))
+            .collect();
+
+        if self.root_id == Some(node_id) {
+            self.root_id = None;
+        }
+        self.unlink(node_id);
+
+        let mut tree = TreeBuilder::new().with_capacity(sub_tree.len()).build();
+        let mut id_map = IdMap::with_capacity(sub_tree.len());
+        for (old_id, old_parent_id) in sub_tree {
+            let data = self.core_tree.remove(old_id).expect("node must exist");
+            let new_id = match old_parent_id.and_then(|id| id_map.get(&id)) {
+                Some(&parent_id) => tree.new_node_mut(parent_id).append(data).node_id(),
+                None => tree.set_root(data),
+            };
+            id_map.insert(old_id, new_id);
+        }
+        Some((tree, id_map))
+    }
+
+    ///
+    /// Removes the sub-tree rooted at the `Node` identified by `node_id` from the `Tree` and
+    /// returns an `Iterator` over the data of its `Node`s, in the given `Order`.  Each `Node` is
+    /// removed as its data is yielded; the sub-tree is detached from the rest of the `Tree` right
+    /// away, and whatever hasn't been yielded yet is removed when the `Iterator` is dropped.  Use
+    /// `DrainSubtree::with_depth` to also get the depth of each `Node` below `node_id`.
+    ///
+    /// Returns a `None`-value if the `Node` doesn't exist.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    /// use nary_tree::behaviors::Order;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let one_id = {
+    ///     let mut root = tree.root_mut().expect("root doesn't exist?");
+    ///     let mut one = root.append(1);
+    ///     one.append(2).append(3);
+    ///     one.append(4);
+    ///     let one_id = one.node_id();
+    ///     root.append(5);
+    ///     one_id
+    /// };
+    ///
+    /// let drained: Vec<(usize, i32)> = tree.drain_subtree(one_id, Order::Post).unwrap()
+    ///     .with_depth()
+    ///     .collect();
+    /// assert_eq!(drained, vec![(2, 3), (1, 2), (1, 4), (0, 1)]);
+    /// assert!(tree.get(one_id).is_none());
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 0
+    /// └── 5
+    /// ");
+    /// ```
+    ///
+    pub fn drain_subtree(&mut self, node_id: NodeId, order: Order) -> Option<DrainSubtree<'_, T>> {
+        self.get_node(node_id)?;
+        let node_ids = self.subtree_ids(node_id, order);
+        if self.root_id == Some(node_id) {
+            self.root_id = None;
+        }
+        self.unlink(node_id);
+        Some(DrainSubtree::new(node_ids, self))
+    }
+
+    ///
+    /// Consumes the `Tree` and returns an `Iterator` over the data of its `Node`s in depth-first
+    /// pre-order, each paired with its depth (the root has a depth of 0).  Use
+    /// `IntoIter::with_parents` to pair each `Node`'s data with the index of its parent in the
+    /// same sequence instead.  Orphaned `Node`s are dropped.
+    ///
+    /// This is what `Tree`'s `IntoIterator` implementation returns.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// root.append(1).append(2);
+    /// root.append(3);
+    ///
+    /// let records: Vec<(Option<usize>, i32)> = tree.into_pre_order().with_parents().collect();
+    /// assert_eq!(records, vec![(None, 0), (Some(0), 1), (Some(1), 2), (Some(0), 3)]);
+    /// ```
+    ///
+    pub fn into_pre_order(self) -> IntoIter<T> {
+        self.into_iter_in(Order::Pre)
+    }
+
+    ///
+    /// Consumes the `Tree` and returns an `Iterator` over the data of its `Node`s in depth-first
+    /// post-order, each paired with its depth (the root has a depth of 0).  Use
+    /// `IntoIter::with_parents` to pair each `Node`'s data with the index of its parent in the
+    /// same sequence instead.  Orphaned `Node`s are dropped.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// root.append(1).append(2);
+    /// root.append(3);
+    ///
+    /// let records: Vec<(usize, i32)> = tree.into_post_order().collect();
+    /// assert_eq!(records, vec![(2, 2), (1, 1), (1, 3), (0, 0)]);
+    /// ```
+    ///
+    pub fn into_post_order(self) -> IntoIter<T> {
+        self.into_iter_in(Order::Post)
+    }
+
+    fn into_iter_in(self, order: Order) -> IntoIter<T> {
+        let node_ids = match self.root_id {
+            Some(root_id) => self.subtree_ids(root_id, order),
+            None => Vec::new(),
+        };
+        let positions: HashMap<NodeId, usize> = node_ids
+            .iter()
+            .enumerate()
+            .map(|(i, &(_, node_id))| (node_id, i))
+            .collect();
+        let records = node_ids
+            .iter()
+            .map(|&(depth, node_id)| {
+                let parent = self
+                    .get_node_relatives(node_id)
+                    .parent
+                    .map(|parent_id| positions[&parent_id]);
+                (depth, parent, node_id)
+            })
+            .collect();
+        IntoIter::new(records, self.core_tree)
+    }
+
+    ///
+    /// Builds a new `Tree` with the same shape as this one, holding `f(data)` for the data of each
+    /// `Node`.  Child order is kept and orphaned sub-trees stay orphaned.  `f` is called in
+    /// pre-order, starting with the rooted `Tree`.  Returns the new `Tree` along with a map from
+    /// the `NodeId`s of this `Tree` to the matching `NodeId`s of the new one.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(1).build();
+    /// let two_id = {
+    ///     let mut root = tree.root_mut().expect("root doesn't exist?");
+    ///     let two_id = root.append(2).node_id();
+    ///     root.append(3);
+    ///     two_id
+    /// };
+    ///
+    /// let (strings, id_map) = tree.map(|data| data.to_string());
+    /// assert_eq!(strings.get(id_map[&two_id]).unwrap().data(), "2");
+    ///
+    /// let mut s = String::new();
+    /// strings.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 1
+    /// ├── 2
+    /// └── 3
+    /// ");
+    /// ```
+    ///
+    pub fn map<U, F>(&self, mut f: F) -> (Tree<U>, IdMap)
+    where
+        F: FnMut(&T) -> U,
+    {
+        let Ok(mapped) = Tree::from_mapped(&self.mapping_order(), self.root_id, |node_id| {
+            let data = &self.get_node(node_id).expect("node must exist").data;
+            Ok::<_, Infallible>(Some(f(data)))
+        });
+        mapped
+    }
+
+    ///
+    /// Consumes the `Tree` and builds a new one with the same shape, holding `f(data)` for the data
+    /// of each `Node`.  Works like `map`, but hands the data to `f` by value so that it can be
+    /// moved into the new `Tree`.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(String::from("a")).build();
+    /// tree.root_mut().unwrap().append(String::from("b"));
+    ///
+    /// let (wrapped, _) = tree.into_map(|data| vec![data]);
+    /// let root = wrapped.root().unwrap();
+    /// assert_eq!(root.data(), &vec![String::from("a")]);
+    /// assert_eq!(root.first_child().unwrap().data(), &vec![String::from("b")]);
+    /// ```
+    ///
+    pub fn into_map<U, F>(mut self, mut f: F) -> (Tree<U>, IdMap)
+    where
+        F: FnMut(T) -> U,
+    {
+        let order = self.mapping_order();
+        let core_tree = &mut self.core_tree;
+        let Ok(mapped) = Tree::from_mapped(&order, self.root_id, |node_id| {
+            let data = core_tree.remove(node_id).expect("node must exist");
+            Ok::<_, Infallible>(Some(f(data)))
+        });
+        mapped
+    }
+
+    ///
+    /// Works like `map` with a fallible `f`: stops at the first `Err` that `f` returns and returns
+    /// it.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root("1").build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// root.append("2");
+    ///
+    /// let (numbers, _) = tree.try_map(|data| data.parse::<i32>()).unwrap();
+    /// assert_eq!(numbers.root().unwrap().first_child().unwrap().data(), &2);
+    ///
+    /// tree.root_mut().unwrap().append("three");
+    /// assert!(tree.try_map(|data| data.parse::<i32>()).is_err());
+    /// ```
+    ///
+    pub fn try_map<U, E, F>(&self, mut f: F) -> Result<(Tree<U>, IdMap), E>
+    where
+        F: FnMut(&T) -> Result<U, E>,
+    {
+        Tree::from_mapped(&self.mapping_order(), self.root_id, |node_id| {
+            f(&self.get_node(node_id).expect("node must exist").data).map(Some)
+        })
+    }
+
+    ///
+    /// Works like `map`, except that a `Node` for which `f` returns `None` is left out of the new
+    /// `Tree` along with its whole sub-tree (`f` isn't called for its descendants).  The `NodeId`s
+    /// of the left out `Node`s are missing from the returned map.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// root.append(1).append(2);
+    /// root.append(-3).append(4);
+    /// root.append(5);
+    ///
+    /// let (pruned, id_map) = tree.filter_map(|&data| (data >= 0).then_some(data * 10));
+    /// assert_eq!(id_map.len(), 4);
+    ///
+    /// let mut s = String::new();
+    /// pruned.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 0
+    /// ├── 10
+    /// │   └── 20
+    /// └── 50
+    /// ");
+    /// ```
+    ///
+    pub fn filter_map<U, F>(&self, mut f: F) -> (Tree<U>, IdMap)
+    where
+        F: FnMut(&T) -> Option<U>,
+    {
+        let Ok(mapped) = Tree::from_mapped(&self.mapping_order(), self.root_id, |node_id| {
+            let data = &self.get_node(node_id).expect("node must exist").data;
+            Ok::<_, Infallible>(f(data))
+        });
+        mapped
+    }
+
+    ///
+    /// Walks the `Tree` from its root once and removes every `Node` for which `f` returns false,
+    /// dealing with the children of each removed `Node` according to `behavior`.  Every `Node` is
+    /// passed to `f` at most once, parents before their children.
+    ///
+    /// Children which stay in the rooted `Tree` (with `PromoteChildren` or
+    /// `ReparentChildrenTo`) are checked as well, while dropped or orphaned children are not.  A
+    /// rejected `Node` that can't be removed with the given `behavior` (see `RemoveBehavior`) is
+    /// kept.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    /// use nary_tree::behaviors::RemoveBehavior::*;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// let mut one = root.append(1);
+    /// one.append(2);
+    /// one.append(3).append(4);
+    /// root.append(5).append(6);
+    ///
+    /// tree.retain(|node_ref| node_ref.data() % 2 == 0, PromoteChildren);
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 0
+    /// ├── 2
+    /// ├── 4
+    /// └── 6
+    /// ");
+    /// ```
+    ///
+    pub fn retain<F>(&mut self, mut f: F, behavior: RemoveBehavior)
+    where
+        F: FnMut(&NodeRef<'_, T>) -> bool,
+    {
+        let mut visited = HashSet::new();
+        let mut stack: Vec<NodeId> = self.root_id.into_iter().collect();
+        while let Some(node_id) = stack.pop() {
+            let node_ref = match self.get(node_id) {
+                Some(node_ref) => node_ref,
+                None => continue,
+            };
+            if !visited.insert(node_id) {
+                continue;
             }
-            if let Some(prev) = prev_sibling {
-                self.set_next_sibling(prev, next_sibling);
+            let keep = f(&node_ref);
+            let child_ids: Vec<NodeId> = node_ref.children().map(|c| c.node_id()).collect();
+            if !keep {
+                let removed = self.remove(node_id, behavior).is_some();
+                if removed
+                    && matches!(
+                        behavior,
+                        RemoveBehavior::DropChildren | RemoveBehavior::OrphanChildren
+                    )
+                {
+                    continue;
+                }
             }
-            if let Some(next) = next_sibling {
-                self.set_prev_sibling(next, prev_sibling);
+            stack.extend(child_ids.into_iter().rev());
+        }
+    }
+
+    ///
+    /// Merges every `Node` of the rooted `Tree` which has exactly one child with that child, the
+    /// way a trie is turned into a radix tree.  Each such chain of `Node`s is replaced by a single
+    /// new `Node` in the place of the chain's first `Node` and with the children of its last one.
+    /// Its data is the data of the chain folded from the top with `merge(parent_data,
+    /// child_data)`.
+    ///
+    /// Returns the `NodeId`s of the `Node`s of every chain, which no longer exist.  If `merge`
+    /// panics, the chain being collapsed is lost and the children of its last `Node` are left as
+    /// orphans.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(String::from("")).build();
+    /// let mut root = tree.root_mut().expect("root doesn't exist?");
+    /// let mut usr = root.append(String::from("usr"));
+    /// usr.append(String::from("lib")).append(String::from("rust"));
+    /// usr.append(String::from("bin"));
+    /// root.append(String::from("etc")).append(String::from("hosts"));
+    ///
+    /// let removed = tree.collapse_chains(|parent, child| format!("{parent}/{child}"));
+    /// assert_eq!(removed.len(), 4);
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    ///
+    /// ├── usr
+    /// │   ├── lib/rust
+    /// │   └── bin
+    /// └── etc/hosts
+    /// ");
+    /// ```
+    ///
+    pub fn collapse_chains<F>(&mut self, mut merge: F) -> Vec<NodeId>
+    where
+        F: FnMut(T, T) -> T,
+    {
+        let mut removed = Vec::new();
+        let mut stack: Vec<NodeId> = self.root_id.into_iter().collect();
+        while let Some(mut node_id) = stack.pop() {
+            if self.only_child(node_id).is_some() {
+                node_id = self.collapse_chain(node_id, &mut merge, &mut removed);
             }
+            let child_ids: Vec<NodeId> = self
+                .new_node_ref(node_id)
+                .children()
+                .map(|node_ref| node_ref.node_id())
+                .collect();
+            stack.extend(child_ids.into_iter().rev());
+        }
+        removed
+    }
 
-            match behavior {
-                RemoveBehavior::DropChildren => self.drop_children(node_id),
-                RemoveBehavior::OrphanChildren => self.orphan_children(node_id),
-            };
-            if self.root_id == Some(node_id) {
-                self.root_id = None;
+    ///
+    /// Makes the `Node` identified by `node_id` the root of the `Tree` by reversing the parent
+    /// links on the path between it and the current root.  Each former ancestor is appended as
+    /// the last child of the `Node` that used to be its child; all other children keep their
+    /// order.
+    ///
+    /// Returns true if the `Tree` was re-rooted (or the `Node` already was the root), returns
+    /// false if the `Node` doesn't exist or isn't part of the rooted `Tree`.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(0).build();
+    /// let two_id = {
+    ///     let mut root = tree.root_mut().expect("root doesn't exist?");
+    ///     let mut one = root.append(1);
+    ///     let two_id = one.append(2).node_id();
+    ///     one.append(3);
+    ///     root.append(4);
+    ///     two_id
+    /// };
+    ///
+    /// assert!(tree.reroot(two_id));
+    /// assert_eq!(tree.root_id(), Some(two_id));
+    ///
+    /// let mut s = String::new();
+    /// tree.write_formatted(&mut s).unwrap();
+    /// assert_eq!(&s, "\
+    /// 2
+    /// └── 1
+    ///     ├── 3
+    ///     └── 0
+    ///         └── 4
+    /// ");
+    /// ```
+    ///
+    pub fn reroot(&mut self, node_id: NodeId) -> bool {
+        let path: Vec<NodeId> = match self.get(node_id) {
+            Some(node_ref) if !node_ref.is_orphan() && !node_ref.has_orphaned_ancestor() => {
+                std::iter::once(node_id)
+                    .chain(node_ref.ancestors().map(|ancestor| ancestor.node_id()))
+                    .collect()
             }
-            self.core_tree.remove(node_id)
-        } else {
-            None
+            _ => return false,
+        };
+
+        for &id in &path {
+            self.unlink(id);
+        }
+        self.root_id = Some(node_id);
+        for pair in path.windows(2) {
+            self.new_node_mut(pair[0]).append_node_id(pair[1]);
+        }
+        true
+    }
+
+    ///
+    /// Returns an `Iterator` over the orphaned `Node`s of the `Tree` which are the root of an
+    /// orphaned sub-tree (i.e. `Node`s for which `NodeRef::is_orphan` returns `true`), in no
+    /// particular order.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    /// use nary_tree::behaviors::RemoveBehavior::*;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(1).build();
+    /// let two_id = {
+    ///     let mut root = tree.root_mut().expect("root doesn't exist?");
+    ///     let mut two = root.append(2);
+    ///     two.append(3).append(4);
+    ///     two.node_id()
+    /// };
+    /// tree.insert_orphaned(5);
+    /// tree.remove(two_id, OrphanChildren);
+    ///
+    /// let mut orphans: Vec<i32> = tree.orphans().map(|orphan| *orphan.data()).collect();
+    /// orphans.sort();
+    /// assert_eq!(orphans, vec![3, 5]);
+    /// ```
+    ///
+    pub fn orphans(&self) -> Orphans<'_, T> {
+        Orphans::new(self)
+    }
+
+    ///
+    /// Returns the number of orphaned sub-trees in the `Tree`.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(1).build();
+    /// assert_eq!(tree.orphan_count(), 0);
+    ///
+    /// let orphan_id = tree.insert_orphaned(2);
+    /// tree.get_mut(orphan_id).unwrap().append(3);
+    /// assert_eq!(tree.orphan_count(), 1);
+    /// ```
+    ///
+    pub fn orphan_count(&self) -> usize {
+        self.orphans().count()
+    }
+
+    ///
+    /// Drops every orphaned sub-tree, freeing the space its `Node`s used inside the `Tree`.
+    /// Returns the number of `Node`s that were dropped.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    /// use nary_tree::behaviors::RemoveBehavior::*;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(1).build();
+    /// let two_id = {
+    ///     let mut root = tree.root_mut().expect("root doesn't exist?");
+    ///     let mut two = root.append(2);
+    ///     two.append(3).append(4);
+    ///     two.node_id()
+    /// };
+    /// tree.remove(two_id, OrphanChildren);
+    ///
+    /// assert_eq!(tree.collect_orphans(), 2);
+    /// assert_eq!(tree.orphan_count(), 0);
+    /// assert_eq!(tree.root().unwrap().data(), &1);
+    /// ```
+    ///
+    pub fn collect_orphans(&mut self) -> usize {
+        let orphan_ids: Vec<NodeId> = self.orphans().map(|orphan| orphan.node_id()).collect();
+        let len = self.core_tree.len();
+        for id in orphan_ids {
+            self.remove(id, RemoveBehavior::DropChildren);
         }
+        len - self.core_tree.len()
     }
 
     /// Shrink the capacity of the nary_tree as much as possible without invalidating
@@ -568,6 +1336,294 @@ impl<T> Tree<T> {
         }
     }
 
+    /// Returns every `Node` of the `Tree` along with its parent, the rooted `Tree` first and then
+    /// each orphaned sub-tree, all in pre-order.
+    fn mapping_order(&self) -> Vec<(NodeId, Option<NodeId>)> {
+        let tops: Vec<NodeId> = self
+            .root_id
+            .into_iter()
+            .chain(self.orphans().map(|orphan| orphan.node_id()))
+            .collect();
+        tops.into_iter()
+            .flat_map(|top_id| self.subtree_ids(top_id, Order::Pre))
+            .map(|(_, node_id)| (node_id, self.get_node_relatives(node_id).parent))
+            .collect()
+    }
+
+    /// Builds a `Tree` from `Node`s given by `mapping_order`, with the data `f` returns for each
+    /// of them.  A `Node` for which `f` returns `None` is left out along with its descendants.
+    fn from_mapped<E, F>(
+        order: &[(NodeId, Option<NodeId>)],
+        root_id: Option<NodeId>,
+        mut f: F,
+    ) -> Result<(Tree<T>, IdMap), E>
+    where
+        F: FnMut(NodeId) -> Result<Option<T>, E>,
+    {
+        let mut tree = TreeBuilder::new().with_capacity(order.len()).build();
+        let mut id_map = IdMap::with_capacity(order.len());
+        for &(old_id, old_parent_id) in order {
+            let parent_id = match old_parent_id {
+                Some(old_parent_id) => match id_map.get(&old_parent_id) {
+                    Some(&parent_id) => Some(parent_id),
+                    None => continue, // an ancestor was left out
+                },
+                None => None,
+            };
+            let data = match f(old_id)? {
+                Some(data) => data,
+                None => continue,
+            };
+            let new_id = match parent_id {
+                Some(parent_id) => tree.new_node_mut(parent_id).append(data).node_id(),
+                None if Some(old_id) == root_id => tree.set_root(data),
+                None => tree.insert_orphaned(data),
+            };
+            id_map.insert(old_id, new_id);
+        }
+        Ok((tree, id_map))
+    }
+
+    /// Returns the data of the sub-tree rooted at `node_id` in pre-order, each paired with its
+    /// depth below `node_id`.  Two sub-trees have the same shape and data exactly when these
+    /// sequences are equal, and comparing them lexicographically orders sub-trees by their root's
+    /// data first and then by their children, one by one.
+    pub(crate) fn subtree_shape(&self, node_id: NodeId) -> impl Iterator<Item = (usize, &T)> {
+        self.subtree_ids(node_id, Order::Pre)
+            .into_iter()
+            .map(move |(depth, id)| (depth, &self.get_node(id).expect("node must exist").data))
+    }
+
+    /// Returns the `subtree_shape` of the rooted `Tree`, which is empty if there is no root.
+    fn shape(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.root_id
+            .into_iter()
+            .flat_map(move |root_id| self.subtree_shape(root_id))
+    }
+
+    /// Returns the `NodeId`s of the sub-tree rooted at `node_id` in the given `Order`, each paired
+    /// with its depth below `node_id`.
+    pub(crate) fn subtree_ids(&self, node_id: NodeId, order: Order) -> Vec<(usize, NodeId)> {
+        let mut node_ids = Vec::new();
+        match order {
+            Order::Pre | Order::Post => {
+                // post-order is pre-order with the children visited from last to first, reversed
+                let mut stack = vec![(0, node_id)];
+                while let Some((depth, id)) = stack.pop() {
+                    node_ids.push((depth, id));
+                    let children = self
+                        .new_node_ref(id)
+                        .children()
+                        .map(|c| (depth + 1, c.node_id()));
+                    if order == Order::Pre {
+                        let children: Vec<(usize, NodeId)> = children.collect();
+                        stack.extend(children.into_iter().rev());
+                    } else {
+                        stack.extend(children);
+                    }
+                }
+                if order == Order::Post {
+                    node_ids.reverse();
+                }
+            }
+            Order::Level => {
+                let mut queue = VecDeque::from([(0, node_id)]);
+                while let Some((depth, id)) = queue.pop_front() {
+                    node_ids.push((depth, id));
+                    queue.extend(
+                        self.new_node_ref(id)
+                            .children()
+                            .map(|c| (depth + 1, c.node_id())),
+                    );
+                }
+            }
+        }
+        node_ids
+    }
+
+    /// Disconnects a `Node` from its parent and siblings, fixing up the parent's first/last child
+    /// and the sibling links around it. The `Node` keeps its own children.
+    pub(crate) fn unlink(&mut self, node_id: NodeId) {
+        let Relatives {
+            parent,
+            prev_sibling,
+            next_sibling,
+            ..
+        } = self.get_node_relatives(node_id);
+
+        let (is_first_child, is_last_child) = self.is_node_first_last_child(node_id);
+
+        if is_first_child {
+            // parent first child = my next sibling
+            self.set_first_child(parent.expect("parent must exist"), next_sibling);
+        }
+        if is_last_child {
+            // parent last child = my prev sibling
+            self.set_last_child(parent.expect("parent must exist"), prev_sibling);
+        }
+        if let Some(prev) = prev_sibling {
+            self.set_next_sibling(prev, next_sibling);
+        }
+        if let Some(next) = next_sibling {
+            self.set_prev_sibling(next, prev_sibling);
+        }
+
+        self.set_parent(node_id, None);
+        self.set_prev_sibling(node_id, None);
+        self.set_next_sibling(node_id, None);
+    }
+
+    /// Checks that `position` refers to the children of `parent_id` and turns a `Position::Index`
+    /// into the equivalent `Before`/`Last` position.  `node_id`, if given, is left out of the
+    /// children as it is the `Node` about to be linked there.
+    pub(crate) fn check_position(
+        &self,
+        parent_id: NodeId,
+        node_id: Option<NodeId>,
+        position: Position,
+    ) -> Result<Position, MoveError> {
+        match position {
+            Position::Before(sibling_id) | Position::After(sibling_id) => {
+                let sibling_parent = self
+                    .get_node(sibling_id)
+                    .and_then(|sibling| sibling.relatives.parent);
+                if sibling_parent != Some(parent_id) {
+                    return Err(MoveError::NotAChild(sibling_id));
+                }
+                Ok(position)
+            }
+            Position::Index(index) => {
+                let children: Vec<NodeId> = self
+                    .new_node_ref(parent_id)
+                    .children()
+                    .map(|child| child.node_id())
+                    .filter(|&child_id| Some(child_id) != node_id)
+                    .collect();
+                match children.get(index) {
+                    Some(&sibling_id) => Ok(Position::Before(sibling_id)),
+                    None if index == children.len() => Ok(Position::Last),
+                    None => Err(MoveError::IndexOutOfBounds(index)),
+                }
+            }
+            Position::First | Position::Last => Ok(position),
+        }
+    }
+
+    /// Moves every `Node` of `core_tree` into this `Tree`, keeping their relationships intact, and
+    /// returns how their `NodeId`s were translated.  The moved `Node`s are left as orphans.
+    pub(crate) fn absorb(&mut self, core_tree: CoreTree<T>) -> IdMap {
+        let nodes: Vec<(NodeId, Node<T>)> = core_tree.into_nodes().collect();
+        self.core_tree.reserve(nodes.len());
+
+        let mut id_map = IdMap::with_capacity(nodes.len());
+        let mut relatives = Vec::with_capacity(nodes.len());
+        for (old_id, node) in nodes {
+            let new_id = self.core_tree.insert(node.data);
+            id_map.insert(old_id, new_id);
+            relatives.push((new_id, node.relatives));
+        }
+
+        let translate = |id: Option<NodeId>| id.map(|id| id_map[&id]);
+        for (new_id, old) in relatives {
+            let node = self.get_node_mut(new_id).expect("node was just inserted");
+            node.relatives = Relatives {
+                parent: translate(old.parent),
+                prev_sibling: translate(old.prev_sibling),
+                next_sibling: translate(old.next_sibling),
+                first_child: translate(old.first_child),
+                last_child: translate(old.last_child),
+            };
+        }
+        id_map
+    }
+
+    /// Links a detached `Node` under `parent_id` at a `Position` returned by `check_position`.
+    pub(crate) fn link_at(&mut self, node_id: NodeId, parent_id: NodeId, position: Position) {
+        match position {
+            Position::First => {
+                NodeMut::new(parent_id, self).prepend_node_id(node_id);
+            }
+            Position::Last | Position::Index(_) => {
+                NodeMut::new(parent_id, self).append_node_id(node_id);
+            }
+            Position::Before(sibling_id) => self.link_before(node_id, sibling_id),
+            Position::After(sibling_id) => self.link_after(node_id, sibling_id),
+        }
+    }
+
+    pub(crate) fn sort_children_by<F>(&mut self, parent_id: NodeId, compare: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut child_ids: Vec<NodeId> = self
+            .new_node_ref(parent_id)
+            .children()
+            .map(|node_ref| node_ref.node_id())
+            .collect();
+        if child_ids.len() < 2 {
+            return;
+        }
+        child_ids.sort_by(|&a, &b| {
+            let a = &self.get_node(a).expect("child must exist").data;
+            let b = &self.get_node(b).expect("child must exist").data;
+            compare(a, b)
+        });
+        self.relink_children(parent_id, &child_ids);
+    }
+
+    /// Rewrites the sibling links of the children of `parent_id` so that they appear in the order
+    /// of `child_ids`, which must hold exactly the current children of `parent_id`.
+    pub(crate) fn relink_children(&mut self, parent_id: NodeId, child_ids: &[NodeId]) {
+        let mut prev_id = None;
+        for (i, &child_id) in child_ids.iter().enumerate() {
+            self.set_prev_sibling(child_id, prev_id);
+            self.set_next_sibling(child_id, child_ids.get(i + 1).copied());
+            prev_id = Some(child_id);
+        }
+        self.set_first_child(parent_id, child_ids.first().copied());
+        self.set_last_child(parent_id, child_ids.last().copied());
+    }
+
+    /// Links a detached `Node` in as the previous sibling of `sibling_id`, under the same parent.
+    pub(crate) fn link_before(&mut self, node_id: NodeId, sibling_id: NodeId) {
+        let Relatives {
+            parent,
+            prev_sibling,
+            ..
+        } = self.get_node_relatives(sibling_id);
+
+        self.set_parent(node_id, parent);
+        self.set_prev_sibling(node_id, prev_sibling);
+        self.set_next_sibling(node_id, Some(sibling_id));
+        self.set_prev_sibling(sibling_id, Some(node_id));
+
+        if let Some(prev) = prev_sibling {
+            self.set_next_sibling(prev, Some(node_id));
+        } else if let Some(parent) = parent {
+            self.set_first_child(parent, Some(node_id));
+        }
+    }
+
+    /// Links a detached `Node` in as the next sibling of `sibling_id`, under the same parent.
+    pub(crate) fn link_after(&mut self, node_id: NodeId, sibling_id: NodeId) {
+        let Relatives {
+            parent,
+            next_sibling,
+            ..
+        } = self.get_node_relatives(sibling_id);
+
+        self.set_parent(node_id, parent);
+        self.set_prev_sibling(node_id, Some(sibling_id));
+        self.set_next_sibling(node_id, next_sibling);
+        self.set_next_sibling(sibling_id, Some(node_id));
+
+        if let Some(next) = next_sibling {
+            self.set_prev_sibling(next, Some(node_id));
+        } else if let Some(parent) = parent {
+            self.set_last_child(parent, Some(node_id));
+        }
+    }
+
     fn drop_children(&mut self, node_id: NodeId) {
         let sub_tree_ids: Vec<NodeId> = self
             .get(node_id)
@@ -577,29 +1633,156 @@ impl<T> Tree<T> {
             .map(|node_ref| node_ref.node_id())
             .collect();
 
-        for id in sub_tree_ids {
-            self.core_tree.remove(id);
+        for id in sub_tree_ids {
+            self.core_tree.remove(id);
+        }
+    }
+
+    fn orphan_children(&mut self, node_id: NodeId) {
+        let child_ids: Vec<NodeId> = self
+            .get(node_id)
+            .expect("node must exist")
+            .children()
+            .map(|node_ref| node_ref.node_id())
+            .collect();
+
+        for id in child_ids {
+            self.set_parent(id, None);
+            self.set_prev_sibling(id, None);
+            self.set_next_sibling(id, None);
+        }
+    }
+
+    fn promote_children(&mut self, node_id: NodeId) -> Result<(), RemoveError> {
+        let child_ids: Vec<NodeId> = self
+            .get(node_id)
+            .expect("node must exist")
+            .children()
+            .map(|node_ref| node_ref.node_id())
+            .collect();
+
+        if self.root_id == Some(node_id) {
+            match child_ids[..] {
+                [] => {}
+                [child_id] => {
+                    self.unlink(child_id);
+                    self.root_id = Some(child_id);
+                }
+                _ => return Err(RemoveError::RootHasManyChildren),
+            }
+        } else if self.get_node_relatives(node_id).parent.is_some() {
+            for id in child_ids {
+                self.unlink(id);
+                self.link_before(id, node_id);
+            }
+        } else {
+            // the node is an orphan itself, so its children can only become orphans
+            for id in child_ids {
+                self.unlink(id);
+            }
+        }
+        Ok(())
+    }
+
+    fn reparent_children(&mut self, node_id: NodeId, parent_id: NodeId) -> Result<(), RemoveError> {
+        match self.get(parent_id) {
+            Some(parent) => {
+                if parent_id == node_id || parent.ancestors().any(|a| a.node_id() == node_id) {
+                    return Err(RemoveError::WouldCreateCycle);
+                }
+            }
+            None => return Err(RemoveError::ParentNotFound(parent_id)),
+        }
+
+        let child_ids: Vec<NodeId> = self
+            .get(node_id)
+            .expect("node must exist")
+            .children()
+            .map(|node_ref| node_ref.node_id())
+            .collect();
+
+        for id in child_ids {
+            self.unlink(id);
+            self.new_node_mut(parent_id).append_node_id(id);
         }
+        Ok(())
     }
 
-    fn orphan_children(&mut self, node_id: NodeId) {
+    fn only_child(&self, node_id: NodeId) -> Option<NodeId> {
+        let Relatives {
+            first_child,
+            last_child,
+            ..
+        } = self.get_node_relatives(node_id);
+        first_child.filter(|_| first_child == last_child)
+    }
+
+    /// Replaces the chain of only children starting at `top_id` (which is part of the rooted
+    /// `Tree`) with a new `Node` holding their merged data, and returns the new `Node`'s id.
+    fn collapse_chain<F>(
+        &mut self,
+        top_id: NodeId,
+        merge: &mut F,
+        removed: &mut Vec<NodeId>,
+    ) -> NodeId
+    where
+        F: FnMut(T, T) -> T,
+    {
+        let mut chain = vec![top_id];
+        while let Some(child_id) = self.only_child(chain[chain.len() - 1]) {
+            chain.push(child_id);
+        }
         let child_ids: Vec<NodeId> = self
-            .get(node_id)
-            .expect("node must exist")
+            .new_node_ref(chain[chain.len() - 1])
             .children()
             .map(|node_ref| node_ref.node_id())
             .collect();
 
-        for id in child_ids {
-            self.set_parent(id, None);
+        // the children wait as orphans and the chain is taken out of the tree before `merge`
+        // runs, so that the tree stays consistent if it panics
+        for &child_id in &child_ids {
+            self.unlink(child_id);
+        }
+        let Relatives {
+            parent,
+            prev_sibling,
+            next_sibling,
+            ..
+        } = self.get_node_relatives(top_id);
+        if self.root_id == Some(top_id) {
+            self.root_id = None;
+        }
+        self.unlink(top_id);
+        let chain_data: Vec<T> = chain
+            .iter()
+            .map(|&id| self.core_tree.remove(id).expect("chain node must exist"))
+            .collect();
+        removed.extend(chain);
+
+        let data = chain_data
+            .into_iter()
+            .reduce(&mut *merge)
+            .expect("chain is never empty");
+        let new_id = self.core_tree.insert(data);
+        match (prev_sibling, next_sibling, parent) {
+            (Some(prev_id), _, _) => self.link_after(new_id, prev_id),
+            (None, Some(next_id), _) => self.link_before(new_id, next_id),
+            (None, None, Some(parent_id)) => {
+                self.new_node_mut(parent_id).append_node_id(new_id);
+            }
+            (None, None, None) => self.root_id = Some(new_id),
+        }
+        for child_id in child_ids {
+            self.new_node_mut(new_id).append_node_id(child_id);
         }
+        new_id
     }
 
-    fn new_node_ref(&self, node_id: NodeId) -> NodeRef<T> {
+    fn new_node_ref(&self, node_id: NodeId) -> NodeRef<'_, T> {
         NodeRef::new(node_id, self)
     }
 
-    fn new_node_mut(&mut self, node_id: NodeId) -> NodeMut<T> {
+    fn new_node_mut(&mut self, node_id: NodeId) -> NodeMut<'_, T> {
         NodeMut::new(node_id, self)
     }
 
@@ -678,12 +1861,155 @@ impl<T: PartialEq> Tree<T> {
     }
 }
 
+impl<T: Clone> Tree<T> {
+    ///
+    /// Clones the `Tree` like `clone` does, and also returns a map from the `NodeId`s of this
+    /// `Tree` to the matching `NodeId`s of the clone.
+    ///
+    /// ```
+    /// use nary_tree::tree::TreeBuilder;
+    ///
+    /// let mut tree = TreeBuilder::new().with_root(1).build();
+    /// let two_id = tree.root_mut().unwrap().append(2).node_id();
+    ///
+    /// let (clone, id_map) = tree.clone_with_id_map();
+    /// assert_ne!(id_map[&two_id], two_id);
+    /// assert_eq!(clone.get(id_map[&two_id]).unwrap().data(), &2);
+    /// assert!(clone.get(two_id).is_none());
+    /// ```
+    ///
+    pub fn clone_with_id_map(&self) -> (Tree<T>, IdMap) {
+        self.map(T::clone)
+    }
+
+    /// Clones the sub-tree rooted at `node_id` into a new, compact `Tree`.
+    pub(crate) fn clone_subtree_from(&self, node_id: NodeId) -> Tree<T> {
+        let order: Vec<(NodeId, Option<NodeId>)> = self
+            .subtree_ids(node_id, Order::Pre)
+            .into_iter()
+            .map(|(depth, id)| {
+                let parent = if depth == 0 {
+                    None
+                } else {
+                    self.get_node_relatives(id).parent
+                };
+                (id, parent)
+            })
+            .collect();
+        let Ok((tree, _)) = Tree::from_mapped(&order, Some(node_id), |id| {
+            let data = &self.get_node(id).expect("node must exist").data;
+            Ok::<_, Infallible>(Some(data.clone()))
+        });
+        tree
+    }
+}
+
+///
+/// The clone gets its own tree id, so `NodeId`s of this `Tree` can't be used with it (see
+/// `clone_with_id_map`).  Its `Node`s are laid out compactly in pre-order, the rooted `Tree` first
+/// and then each orphaned sub-tree.
+///
+/// ```
+/// use nary_tree::tree::TreeBuilder;
+///
+/// let mut tree = TreeBuilder::new().with_root(1).build();
+/// tree.root_mut().unwrap().append(2);
+///
+/// let clone = tree.clone();
+/// assert_ne!(clone.root_id(), tree.root_id());
+/// assert_eq!(clone.root().unwrap().first_child().unwrap().data(), &2);
+/// ```
+///
+impl<T: Clone> Clone for Tree<T> {
+    fn clone(&self) -> Self {
+        self.clone_with_id_map().0
+    }
+}
+
+///
+/// Two `Tree`s are equal when their rooted `Tree`s have the same shape and hold equal data in
+/// matching `Node`s.  `NodeId`s, the layout of the `Node`s in memory and orphaned `Node`s are
+/// ignored.
+///
+/// ```
+/// use nary_tree::tree;
+/// use nary_tree::tree::TreeBuilder;
+///
+/// let mut tree = TreeBuilder::new().with_capacity(10).with_root(0).build();
+/// let mut root = tree.root_mut().unwrap();
+/// root.append(2);
+/// root.prepend(1);
+/// tree.insert_orphaned(3);
+///
+/// assert_eq!(tree, tree!(0 => [1, 2]));
+/// assert_ne!(tree, tree!(0 => [1 => [2]]));
+/// ```
+///
+impl<T: PartialEq> PartialEq for Tree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.shape().eq(other.shape())
+    }
+}
+
+impl<T: Eq> Eq for Tree<T> {}
+
+///
+/// Hashes the shape and data of the rooted `Tree`, consistently with `PartialEq`.
+///
+impl<T: Hash> Hash for Tree<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let mut len = 0usize;
+        for (depth, data) in self.shape() {
+            depth.hash(state);
+            data.hash(state);
+            len += 1;
+        }
+        len.hash(state);
+    }
+}
+
+///
+/// `Tree`s are ordered by the data of their roots first and then by their roots' children, one by
+/// one, each child being compared the same way.  A `Tree` that runs out of children first is the
+/// lesser one, and an empty `Tree` is less than any other.
+///
+/// ```
+/// use nary_tree::tree;
+/// use nary_tree::tree::Tree;
+///
+/// assert!(tree!(0 => [1]) < tree!(0 => [1, 0]));
+/// assert!(tree!(0 => [1 => [5]]) > tree!(0 => [1, 9]));
+/// assert!(tree!(0 => [2]) > tree!(0 => [1 => [5]]));
+/// assert!(Tree::new() < tree!(0));
+/// ```
+///
+impl<T: PartialOrd> PartialOrd for Tree<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.shape().partial_cmp(other.shape())
+    }
+}
+
+impl<T: Ord> Ord for Tree<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.shape().cmp(other.shape())
+    }
+}
+
 impl<T> Default for Tree<T> {
     fn default() -> Self {
         TreeBuilder::new().build()
     }
 }
 
+impl<T> IntoIterator for Tree<T> {
+    type Item = (usize, T);
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        self.into_pre_order()
+    }
+}
+
 impl<T: std::fmt::Display> Tree<T> {
     /// Write formatted tree representation and nodes with debug formatting.
     ///
@@ -718,74 +2044,84 @@ impl<T: std::fmt::Display> Tree<T> {
     /// assert_eq!(&s, "");
     /// ```
     pub fn write_formatted<W: std::fmt::Write>(&self, w: &mut W) -> std::fmt::Result {
-        if let Some(root) = self.root() {
-            let node_id = root.node_id();
-            let childn = 0;
-            let level = 0;
-            let last = vec![];
-            let multiline = None;
-            let mut stack = vec![(node_id, childn, level, last, multiline)];
-            while let Some((node_id, childn, level, last, multiline)) = stack.pop() {
-                debug_assert_eq!(
-                    last.len(),
-                    level,
-                    "each previous level should indicate whether it has reached the last node"
-                );
-                let node = self
-                    .get(node_id)
-                    .expect("getting node of existing node ref id");
-                if childn == 0 {
-                    for i in 1..level {
-                        if last[i - 1] {
-                            write!(w, "    ")?;
-                        } else {
-                            write!(w, "│   ")?;
-                        }
+        match self.root_id {
+            Some(root_id) => self.write_formatted_from(root_id, w),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes the sub-tree rooted at `node_id` the same way `write_formatted` writes the whole
+    /// `Tree`.
+    pub(crate) fn write_formatted_from<W: std::fmt::Write>(
+        &self,
+        node_id: NodeId,
+        w: &mut W,
+    ) -> std::fmt::Result {
+        let childn = 0;
+        let level = 0;
+        let last = vec![];
+        let multiline = None;
+        let mut stack = vec![(node_id, childn, level, last, multiline)];
+        while let Some((node_id, childn, level, last, multiline)) = stack.pop() {
+            debug_assert_eq!(
+                last.len(),
+                level,
+                "each previous level should indicate whether it has reached the last node"
+            );
+            let node = self
+                .get(node_id)
+                .expect("getting node of existing node ref id");
+            if childn == 0 {
+                for i in 1..level {
+                    if last[i - 1] {
+                        write!(w, "    ")?;
+                    } else {
+                        write!(w, "│   ")?;
                     }
-                    if level > 0 {
-                        if last[level - 1] {
-                            if multiline.is_none() {
-                                write!(w, "└── ")?;
-                            } else {
-                                write!(w, "    ")?;
-                            }
-                        } else if multiline.is_none() {
-                            write!(w, "├── ")?;
+                }
+                if level > 0 {
+                    if last[level - 1] {
+                        if multiline.is_none() {
+                            write!(w, "└── ")?;
                         } else {
-                            write!(w, "│   ")?;
+                            write!(w, "    ")?;
                         }
-                    }
-                    let mut display_lines = if let Some(multiline) = multiline {
-                        multiline
+                    } else if multiline.is_none() {
+                        write!(w, "├── ")?;
                     } else {
-                        let mut display_lines = String::new();
-                        write!(&mut display_lines, "{}", node.data())?;
-                        display_lines
-                            .lines()
-                            .map(|s| s.to_string())
-                            .rev()
-                            .collect::<Vec<_>>()
-                    };
-                    if let Some(line) = display_lines.pop() {
-                        writeln!(w, "{line}")?;
-                        if !display_lines.is_empty() {
-                            stack.push((node_id, childn, level, last, Some(display_lines.clone())));
-                            continue;
-                        }
+                        write!(w, "│   ")?;
                     }
                 }
-                let mut children = node.children().skip(childn);
-                if let Some(child) = children.next() {
-                    let mut next_last = last.clone();
-                    if children.next().is_some() {
-                        stack.push((node_id, childn + 1, level, last, None));
-                        next_last.push(false);
-                    } else {
-                        next_last.push(true);
+                let mut display_lines = if let Some(multiline) = multiline {
+                    multiline
+                } else {
+                    let mut display_lines = String::new();
+                    write!(&mut display_lines, "{}", node.data())?;
+                    display_lines
+                        .lines()
+                        .map(|s| s.to_string())
+                        .rev()
+                        .collect::<Vec<_>>()
+                };
+                if let Some(line) = display_lines.pop() {
+                    writeln!(w, "{line}")?;
+                    if !display_lines.is_empty() {
+                        stack.push((node_id, childn, level, last, Some(display_lines.clone())));
+                        continue;
                     }
-                    stack.push((child.node_id(), 0, level + 1, next_last, None));
                 }
             }
+            let mut children = node.children().skip(childn);
+            if let Some(child) = children.next() {
+                let mut next_last = last.clone();
+                if children.next().is_some() {
+                    stack.push((node_id, childn + 1, level, last, None));
+                    next_last.push(false);
+                } else {
+                    next_last.push(true);
+                }
+                stack.push((child.node_id(), 0, level + 1, next_last, None));
+            }
         }
         Ok(())
     }
@@ -797,11 +2133,42 @@ impl<T: Display> Display for Tree<T> {
     }
 }
 
+/// Returns the index of the first `Node` whose parent links go around a cycle back to it.  Every
+/// parent must be one of the keys.
+fn first_in_cycle<K: Eq + Hash>(
+    parents: &[Option<K>],
+    indexes: &HashMap<K, usize>,
+) -> Option<usize> {
+    // walked[i] is 1 + the index of the first walk that reached node i
+    let mut walked = vec![0; parents.len()];
+    let mut on_cycle = vec![false; parents.len()];
+    for start in 0..parents.len() {
+        let mut i = start;
+        while walked[i] == 0 {
+            walked[i] = start + 1;
+            match &parents[i] {
+                Some(parent) => i = indexes[parent],
+                None => break,
+            }
+        }
+        if walked[i] == start + 1 && !on_cycle[i] && parents[i].is_some() {
+            // this walk came back around to a node it already went through
+            while !on_cycle[i] {
+                on_cycle[i] = true;
+                i = indexes[parents[i].as_ref().expect("nodes on a cycle have parents")];
+            }
+        }
+    }
+    on_cycle.iter().position(|&is_on_cycle| is_on_cycle)
+}
+
 #[cfg_attr(tarpaulin, skip)]
 #[cfg(test)]
 mod tree_tests {
     use super::*;
-    use crate::behaviors::RemoveBehavior::{DropChildren, OrphanChildren};
+    use crate::behaviors::RemoveBehavior::{
+        DropChildren, OrphanChildren, PromoteChildren, ReparentChildrenTo,
+    };
 
     #[test]
     fn capacity() {
@@ -934,28 +2301,270 @@ mod tree_tests {
     }
 
     #[test]
-    fn get_node_mut() {
+    fn get_node_mut() {
+        let mut tree = TreeBuilder::new().with_root(1).build();
+
+        let root_id = tree.root_id().expect("root doesn't exist?");
+        let root = tree.get_node_mut(root_id);
+        assert!(root.is_some());
+
+        let root = root.unwrap();
+        assert_eq!(root.data, 1);
+
+        root.data = 2;
+        assert_eq!(root.data, 2);
+    }
+
+    #[test]
+    fn remove_drop() {
+        let mut tree = TreeBuilder::new().with_root(1).build();
+
+        let two_id;
+        let three_id;
+        let four_id;
+        let five_id;
+        {
+            let mut root = tree.root_mut().expect("root doesn't exist?");
+            two_id = root.append(2).node_id();
+            three_id = root.append(3).node_id();
+            four_id = root.append(4).node_id();
+        }
+        {
+            five_id = tree
+                .get_mut(three_id)
+                .expect("three doesn't exist?")
+                .append(5)
+                .node_id();
+        }
+
+        //        1
+        //      / | \
+        //     2  3  4
+        //        |
+        //        5
+
+        tree.remove(three_id, DropChildren);
+
+        let root = tree
+            .get_node(tree.root_id().expect("tree doesn't exist?"))
+            .unwrap();
+        assert!(root.relatives.first_child.is_some());
+        assert!(root.relatives.last_child.is_some());
+        assert_eq!(root.relatives.first_child.unwrap(), two_id);
+        assert_eq!(root.relatives.last_child.unwrap(), four_id);
+
+        let two = tree.get_node(two_id);
+        assert!(two.is_some());
+
+        let two = two.unwrap();
+        assert_eq!(two.relatives.next_sibling, Some(four_id));
+
+        let four = tree.get_node(four_id);
+        assert!(four.is_some());
+
+        let four = four.unwrap();
+        assert_eq!(four.relatives.prev_sibling, Some(two_id));
+
+        let five = tree.get_node(five_id);
+        assert!(five.is_none());
+    }
+
+    /// Test that there is no panic if caller tries to remove a removed node
+    #[test]
+    fn address_dropped() {
+        let mut tree = TreeBuilder::new().with_root(1).build();
+        let two_id = tree.root_mut().expect("root doesn't exist").node_id();
+        tree.remove(two_id, DropChildren);
+        tree.remove(two_id, DropChildren);
+    }
+
+    #[test]
+    fn remove_orphan() {
+        let mut tree = TreeBuilder::new().with_root(1).build();
+
+        let two_id;
+        let three_id;
+        let four_id;
+        let five_id;
+        {
+            let mut root = tree.root_mut().expect("root doesn't exist?");
+            two_id = root.append(2).node_id();
+            three_id = root.append(3).node_id();
+            four_id = root.append(4).node_id();
+        }
+        {
+            five_id = tree
+                .get_mut(three_id)
+                .expect("three doesn't exist?")
+                .append(5)
+                .node_id();
+        }
+
+        //        1
+        //      / | \
+        //     2  3  4
+        //        |
+        //        5
+
+        tree.remove(three_id, OrphanChildren);
+
+        let root = tree
+            .get_node(tree.root_id().expect("tree doesn't exist?"))
+            .unwrap();
+        assert!(root.relatives.first_child.is_some());
+        assert!(root.relatives.last_child.is_some());
+        assert_eq!(root.relatives.first_child.unwrap(), two_id);
+        assert_eq!(root.relatives.last_child.unwrap(), four_id);
+
+        let two = tree.get_node(two_id);
+        assert!(two.is_some());
+
+        let two = two.unwrap();
+        assert_eq!(two.relatives.next_sibling, Some(four_id));
+
+        let four = tree.get_node(four_id);
+        assert!(four.is_some());
+
+        let four = four.unwrap();
+        assert_eq!(four.relatives.prev_sibling, Some(two_id));
+
+        let five = tree.get_node(five_id);
+        assert!(five.is_some());
+
+        let five = five.unwrap();
+        assert_eq!(five.relatives.parent, None);
+    }
+
+    #[test]
+    fn remove_promote() {
+        let mut tree = TreeBuilder::new().with_root(1).build();
+
+        let two_id;
+        let three_id;
+        let four_id;
+        let five_id;
+        let six_id;
+        {
+            let mut root = tree.root_mut().expect("root doesn't exist?");
+            two_id = root.append(2).node_id();
+            three_id = root.append(3).node_id();
+            four_id = root.append(4).node_id();
+        }
+        {
+            let mut three = tree.get_mut(three_id).expect("three doesn't exist?");
+            five_id = three.append(5).node_id();
+            six_id = three.append(6).node_id();
+        }
+
+        //        1
+        //      / | \
+        //     2  3  4
+        //       / \
+        //      5   6
+
+        assert_eq!(tree.remove(three_id, PromoteChildren), Some(3));
+
+        let root_id = tree.root_id().expect("tree doesn't exist?");
+        let children: Vec<_> = tree
+            .get(root_id)
+            .unwrap()
+            .children()
+            .map(|c| c.node_id())
+            .collect();
+        assert_eq!(children, vec![two_id, five_id, six_id, four_id]);
+
+        let five = tree.get_node(five_id).unwrap();
+        assert_eq!(five.relatives.parent, Some(root_id));
+        assert_eq!(five.relatives.prev_sibling, Some(two_id));
+        assert_eq!(five.relatives.next_sibling, Some(six_id));
+        let six = tree.get_node(six_id).unwrap();
+        assert_eq!(six.relatives.next_sibling, Some(four_id));
+        assert_eq!(
+            tree.get_node(four_id).unwrap().relatives.prev_sibling,
+            Some(six_id)
+        );
+        assert!(tree.get_node(three_id).is_none());
+    }
+
+    #[test]
+    fn remove_promote_first_and_last() {
+        let mut tree = TreeBuilder::new().with_root(1).build();
+        let root_id = tree.root_id().unwrap();
+        let two_id = tree.root_mut().unwrap().append(2).node_id();
+        let three_id = tree.get_mut(two_id).unwrap().append(3).node_id();
+
+        assert_eq!(tree.remove(two_id, PromoteChildren), Some(2));
+
+        let root = tree.get_node(root_id).unwrap();
+        assert_eq!(root.relatives.first_child, Some(three_id));
+        assert_eq!(root.relatives.last_child, Some(three_id));
+        let three = tree.get_node(three_id).unwrap();
+        assert_eq!(three.relatives.parent, Some(root_id));
+        assert_eq!(three.relatives.prev_sibling, None);
+        assert_eq!(three.relatives.next_sibling, None);
+    }
+
+    #[test]
+    fn remove_promote_root() {
         let mut tree = TreeBuilder::new().with_root(1).build();
+        let root_id = tree.root_id().unwrap();
+        let two_id = tree.root_mut().unwrap().append(2).node_id();
+        let three_id = tree.get_mut(two_id).unwrap().append(3).node_id();
+
+        assert_eq!(tree.remove(root_id, PromoteChildren), Some(1));
+        assert_eq!(tree.root_id(), Some(two_id));
+        let two = tree.get(two_id).unwrap();
+        assert!(two.parent().is_none());
+        assert!(!two.is_orphan());
+        assert_eq!(two.first_child().unwrap().node_id(), three_id);
+
+        assert_eq!(tree.remove(two_id, PromoteChildren), Some(2));
+        assert_eq!(tree.remove(three_id, PromoteChildren), Some(3));
+        assert!(tree.root_id().is_none());
+    }
 
-        let root_id = tree.root_id().expect("root doesn't exist?");
-        let root = tree.get_node_mut(root_id);
-        assert!(root.is_some());
+    #[test]
+    fn remove_promote_root_with_several_children() {
+        let mut tree = TreeBuilder::new().with_root(1).build();
+        let root_id = tree.root_id().unwrap();
+        let mut root = tree.root_mut().unwrap();
+        let two_id = root.append(2).node_id();
+        let three_id = root.append(3).node_id();
+
+        assert_eq!(tree.remove(root_id, PromoteChildren), None);
+        assert_eq!(
+            tree.try_remove(root_id, PromoteChildren),
+            Err(RemoveError::RootHasManyChildren)
+        );
+        assert_eq!(tree.root_id(), Some(root_id));
+        let children: Vec<_> = tree
+            .root()
+            .unwrap()
+            .children()
+            .map(|c| c.node_id())
+            .collect();
+        assert_eq!(children, vec![two_id, three_id]);
+    }
 
-        let root = root.unwrap();
-        assert_eq!(root.data, 1);
+    #[test]
+    fn remove_promote_orphan() {
+        let mut tree = TreeBuilder::new().with_root(1).build();
+        let orphan_id = tree.insert_orphaned(10);
+        let child_id = tree.get_mut(orphan_id).unwrap().append(11).node_id();
 
-        root.data = 2;
-        assert_eq!(root.data, 2);
+        assert_eq!(tree.remove(orphan_id, PromoteChildren), Some(10));
+        assert!(tree.get(child_id).unwrap().is_orphan());
     }
 
     #[test]
-    fn remove_drop() {
+    fn remove_reparent() {
         let mut tree = TreeBuilder::new().with_root(1).build();
 
         let two_id;
         let three_id;
         let four_id;
         let five_id;
+        let six_id;
         {
             let mut root = tree.root_mut().expect("root doesn't exist?");
             two_id = root.append(2).node_id();
@@ -963,62 +2572,371 @@ mod tree_tests {
             four_id = root.append(4).node_id();
         }
         {
-            five_id = tree
-                .get_mut(three_id)
-                .expect("three doesn't exist?")
-                .append(5)
-                .node_id();
+            let mut three = tree.get_mut(three_id).expect("three doesn't exist?");
+            five_id = three.append(5).node_id();
+            six_id = three.append(6).node_id();
         }
+        let seven_id = tree.get_mut(two_id).unwrap().append(7).node_id();
 
-        //        1
-        //      / | \
-        //     2  3  4
-        //        |
-        //        5
+        assert_eq!(tree.remove(three_id, ReparentChildrenTo(two_id)), Some(3));
 
-        tree.remove(three_id, DropChildren);
+        let children: Vec<_> = tree
+            .get(two_id)
+            .unwrap()
+            .children()
+            .map(|c| c.node_id())
+            .collect();
+        assert_eq!(children, vec![seven_id, five_id, six_id]);
+        let root = tree.root().unwrap();
+        let children: Vec<_> = root.children().map(|c| c.node_id()).collect();
+        assert_eq!(children, vec![two_id, four_id]);
+
+        let six = tree.get_node(six_id).unwrap();
+        assert_eq!(six.relatives.parent, Some(two_id));
+        assert_eq!(six.relatives.next_sibling, None);
+        assert_eq!(
+            tree.get_node(two_id).unwrap().relatives.last_child,
+            Some(six_id)
+        );
+    }
 
-        let root = tree
-            .get_node(tree.root_id().expect("tree doesn't exist?"))
-            .unwrap();
-        assert!(root.relatives.first_child.is_some());
-        assert!(root.relatives.last_child.is_some());
-        assert_eq!(root.relatives.first_child.unwrap(), two_id);
-        assert_eq!(root.relatives.last_child.unwrap(), four_id);
+    #[test]
+    fn remove_reparent_into_own_subtree() {
+        let mut tree = TreeBuilder::new().with_root(1).build();
+        let two_id = tree.root_mut().unwrap().append(2).node_id();
+        let three_id = tree.get_mut(two_id).unwrap().append(3).node_id();
+        let gone_id = tree.root_mut().unwrap().append(4).node_id();
+        tree.remove(gone_id, DropChildren);
+
+        assert_eq!(tree.remove(two_id, ReparentChildrenTo(three_id)), None);
+        assert_eq!(
+            tree.try_remove(two_id, ReparentChildrenTo(three_id)),
+            Err(RemoveError::WouldCreateCycle)
+        );
+        assert_eq!(
+            tree.try_remove(two_id, ReparentChildrenTo(two_id)),
+            Err(RemoveError::WouldCreateCycle)
+        );
+        assert_eq!(
+            tree.try_remove(two_id, ReparentChildrenTo(gone_id)),
+            Err(RemoveError::ParentNotFound(gone_id))
+        );
+        assert_eq!(
+            tree.get(three_id).unwrap().parent().unwrap().node_id(),
+            two_id
+        );
+        assert_eq!(
+            tree.root().unwrap().first_child().unwrap().node_id(),
+            two_id
+        );
+    }
 
-        let two = tree.get_node(two_id);
-        assert!(two.is_some());
+    fn build_retain_tree() -> Tree<i32> {
+        let mut tree = TreeBuilder::new().with_root(0).build();
+        let mut root = tree.root_mut().unwrap();
+        {
+            let mut one = root.append(1);
+            let mut two = one.append(2);
+            two.append(3);
+            two.append(4);
+        }
+        {
+            let mut five = root.append(5);
+            five.append(6).append(7);
+            five.append(8);
+        }
+        root.append(9);
 
-        let two = two.unwrap();
-        assert_eq!(two.relatives.next_sibling, Some(four_id));
+        // 0
+        // ├── 1
+        // │   └── 2
+        // │       ├── 3
+        // │       └── 4
+        // ├── 5
+        // │   ├── 6
+        // │   │   └── 7
+        // │   └── 8
+        // └── 9
+        tree
+    }
 
-        let four = tree.get_node(four_id);
-        assert!(four.is_some());
+    fn pre_order_data(tree: &Tree<i32>) -> Vec<i32> {
+        tree.root()
+            .map(|root| root.traverse_pre_order().map(|n| *n.data()).collect())
+            .unwrap_or_default()
+    }
 
-        let four = four.unwrap();
-        assert_eq!(four.relatives.prev_sibling, Some(two_id));
+    #[test]
+    fn retain_drop() {
+        let mut tree = build_retain_tree();
+        let mut seen = vec![];
+        tree.retain(
+            |node_ref| {
+                seen.push(*node_ref.data());
+                node_ref.data() % 5 != 0 || *node_ref.data() == 0
+            },
+            DropChildren,
+        );
+        assert_eq!(seen, vec![0, 1, 2, 3, 4, 5, 9]);
+        assert_eq!(pre_order_data(&tree), vec![0, 1, 2, 3, 4, 9]);
+        assert!(tree.find(&7).is_none());
+        assert!(tree.find(&6).is_none());
+    }
 
-        let five = tree.get_node(five_id);
-        assert!(five.is_none());
+    #[test]
+    fn retain_orphan() {
+        let mut tree = build_retain_tree();
+        let six_id = tree.find(&6).unwrap()[0];
+        let mut seen = vec![];
+        tree.retain(
+            |node_ref| {
+                seen.push(*node_ref.data());
+                *node_ref.data() != 5
+            },
+            OrphanChildren,
+        );
+        assert_eq!(seen, vec![0, 1, 2, 3, 4, 5, 9]);
+        assert_eq!(pre_order_data(&tree), vec![0, 1, 2, 3, 4, 9]);
+        assert!(tree.get(six_id).unwrap().is_orphan());
     }
 
-    /// Test that there is no panic if caller tries to remove a removed node
     #[test]
-    fn address_dropped() {
+    fn retain_promote() {
+        let mut tree = build_retain_tree();
+        let mut seen = vec![];
+        tree.retain(
+            |node_ref| {
+                seen.push(*node_ref.data());
+                node_ref.data() % 2 == 0
+            },
+            PromoteChildren,
+        );
+        assert_eq!(seen, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(pre_order_data(&tree), vec![0, 2, 4, 6, 8]);
+        let root = tree.root().unwrap();
+        assert_eq!(root.children().count(), 3);
+        assert_eq!(root.last_child().unwrap().data(), &8);
+    }
+
+    #[test]
+    fn retain_promote_root_with_several_children() {
+        let mut tree = build_retain_tree();
+        tree.retain(|node_ref| *node_ref.data() > 0, PromoteChildren);
+        assert_eq!(pre_order_data(&tree), vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn retain_reparent() {
+        let mut tree = build_retain_tree();
+        let nine_id = tree.find(&9).unwrap()[0];
+        let mut seen = vec![];
+        tree.retain(
+            |node_ref| {
+                seen.push(*node_ref.data());
+                ![2, 5, 7].contains(node_ref.data())
+            },
+            ReparentChildrenTo(nine_id),
+        );
+        assert_eq!(seen, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(pre_order_data(&tree), vec![0, 1, 9, 3, 4, 6, 8]);
+    }
+
+    #[test]
+    fn retain_root() {
+        let mut tree = build_retain_tree();
+        tree.retain(|_| false, DropChildren);
+        assert!(tree.root().is_none());
+
+        let mut empty: Tree<i32> = Tree::new();
+        empty.retain(|_| false, DropChildren);
+        assert!(empty.root().is_none());
+    }
+
+    #[test]
+    fn collapse_chains() {
+        let mut tree = build_retain_tree();
+        let chain_ids: Vec<NodeId> = [1, 2, 6, 7]
+            .iter()
+            .map(|data| tree.find(data).unwrap()[0])
+            .collect();
+        let four_id = tree.find(&4).unwrap()[0];
+        let five_id = tree.find(&5).unwrap()[0];
+        let eight_id = tree.find(&8).unwrap()[0];
+
+        let removed = tree.collapse_chains(|parent, child| parent * 10 + child);
+        assert_eq!(removed, chain_ids);
+        assert!(removed.iter().all(|&id| tree.get(id).is_none()));
+
+        // 0
+        // ├── 12
+        // │   ├── 3
+        // │   └── 4
+        // ├── 5
+        // │   ├── 67
+        // │   └── 8
+        // └── 9
+        assert_eq!(pre_order_data(&tree), vec![0, 12, 3, 4, 5, 67, 8, 9]);
+        let twelve = tree.get(tree.find(&12).unwrap()[0]).unwrap();
+        assert_eq!(twelve.parent().unwrap().node_id(), tree.root_id().unwrap());
+        assert_eq!(twelve.next_sibling().unwrap().node_id(), five_id);
+        assert_eq!(twelve.last_child().unwrap().node_id(), four_id);
+        let sixty_seven = tree.get(tree.find(&67).unwrap()[0]).unwrap();
+        assert!(sixty_seven.prev_sibling().is_none());
+        assert_eq!(sixty_seven.next_sibling().unwrap().node_id(), eight_id);
+        assert_eq!(
+            tree.get(eight_id).unwrap().prev_sibling().unwrap().data(),
+            &67
+        );
+        assert_eq!(tree.orphan_count(), 0);
+    }
+
+    #[test]
+    fn collapse_chains_from_root() {
         let mut tree = TreeBuilder::new().with_root(1).build();
-        let two_id = tree.root_mut().expect("root doesn't exist").node_id();
-        tree.remove(two_id, DropChildren);
-        tree.remove(two_id, DropChildren);
+        let root_id = tree.root_id().unwrap();
+        let mut root = tree.root_mut().unwrap();
+        let mut two = root.append(2);
+        let three_id = two.append(3).node_id();
+        let two_id = two.node_id();
+
+        let removed = tree.collapse_chains(|parent, child| parent * 10 + child);
+        assert_eq!(removed, vec![root_id, two_id, three_id]);
+        assert_eq!(pre_order_data(&tree), vec![123]);
+        assert!(!tree.root().unwrap().is_orphan());
     }
 
     #[test]
-    fn remove_orphan() {
+    fn collapse_chains_panicking_merge() {
+        let mut tree = build_retain_tree();
+        let three_id = tree.find(&3).unwrap()[0];
+        let four_id = tree.find(&4).unwrap()[0];
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            tree.collapse_chains(|_, _| -> i32 { panic!("merge failed") });
+        }));
+        assert!(result.is_err());
+
+        // the chain 1 -> 2 is gone and its children wait as orphans
+        assert_eq!(pre_order_data(&tree), vec![0, 5, 6, 7, 8, 9]);
+        assert!(tree.get(three_id).unwrap().is_orphan());
+        assert!(tree.get(four_id).unwrap().is_orphan());
+        assert_eq!(tree.orphan_count(), 2);
+    }
+
+    #[test]
+    fn reroot() {
+        let mut tree = build_retain_tree();
+        let seven_id = tree.find(&7).unwrap()[0];
+        let six_id = tree.find(&6).unwrap()[0];
+        let five_id = tree.find(&5).unwrap()[0];
+        let old_root_id = tree.root_id().unwrap();
+
+        assert!(tree.reroot(seven_id));
+        assert_eq!(tree.root_id(), Some(seven_id));
+
+        // 7
+        // └── 6
+        //     └── 5
+        //         ├── 8
+        //         └── 0
+        //             ├── 1
+        //             │   └── 2
+        //             │       ├── 3
+        //             │       └── 4
+        //             └── 9
+        assert_eq!(pre_order_data(&tree), vec![7, 6, 5, 8, 0, 1, 2, 3, 4, 9]);
+
+        let seven = tree.get(seven_id).unwrap();
+        assert!(seven.parent().is_none());
+        assert!(!seven.is_orphan());
+        let five = tree.get(five_id).unwrap();
+        assert_eq!(five.parent().unwrap().node_id(), six_id);
+        assert_eq!(five.last_child().unwrap().node_id(), old_root_id);
+        let old_root = tree.get(old_root_id).unwrap();
+        assert_eq!(old_root.prev_sibling().unwrap().data(), &8);
+        assert!(old_root.next_sibling().is_none());
+        assert_eq!(old_root.children().count(), 2);
+
+        assert!(tree.reroot(old_root_id));
+        assert_eq!(pre_order_data(&tree), vec![0, 1, 2, 3, 4, 9, 5, 8, 6, 7]);
+    }
+
+    #[test]
+    fn reroot_root_and_orphans() {
+        let mut tree = build_retain_tree();
+        let root_id = tree.root_id().unwrap();
+        let orphan_id = tree.insert_orphaned(10);
+        let orphan_child_id = tree.get_mut(orphan_id).unwrap().append(11).node_id();
+
+        assert!(tree.reroot(root_id));
+        assert_eq!(pre_order_data(&tree), vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert!(!tree.reroot(orphan_id));
+        assert!(!tree.reroot(orphan_child_id));
+        assert_eq!(tree.root_id(), Some(root_id));
+    }
+
+    #[test]
+    fn orphans() {
+        let mut tree = build_retain_tree();
+        assert_eq!(tree.orphans().count(), 0);
+
+        let two_id = tree.find(&2).unwrap()[0];
+        let three_id = tree.find(&3).unwrap()[0];
+        let four_id = tree.find(&4).unwrap()[0];
+        let five_id = tree.find(&5).unwrap()[0];
+        let six_id = tree.find(&6).unwrap()[0];
+        let orphan_id = tree.insert_orphaned(10);
+        tree.remove(two_id, OrphanChildren);
+        tree.get_mut(five_id).unwrap().detach();
+
+        let mut orphan_ids: Vec<NodeId> = tree.orphans().map(|o| o.node_id()).collect();
+        orphan_ids.sort();
+        let mut expected = vec![three_id, four_id, five_id, orphan_id];
+        expected.sort();
+        assert_eq!(orphan_ids, expected);
+        assert_eq!(tree.orphan_count(), 4);
+        assert!(tree.orphans().all(|o| o.is_orphan()));
+        assert!(!orphan_ids.contains(&six_id));
+    }
+
+    #[test]
+    fn orphans_without_root() {
+        let mut tree: Tree<i32> = Tree::new();
+        let orphan_id = tree.insert_orphaned(1);
+        let orphans: Vec<NodeId> = tree.orphans().map(|o| o.node_id()).collect();
+        assert_eq!(orphans, vec![orphan_id]);
+
+        tree.set_root(0);
+        assert_eq!(tree.orphan_count(), 1);
+    }
+
+    #[test]
+    fn collect_orphans() {
+        let mut tree = build_retain_tree();
+        let five_id = tree.find(&5).unwrap()[0];
+        let six_id = tree.find(&6).unwrap()[0];
+        let one_id = tree.find(&1).unwrap()[0];
+        tree.get_mut(five_id).unwrap().detach();
+        tree.remove(one_id, OrphanChildren);
+        tree.insert_orphaned(10);
+
+        // 5 -> 6 -> 7, 8; 2 -> 3, 4; 10
+        assert_eq!(tree.collect_orphans(), 8);
+        assert_eq!(tree.orphan_count(), 0);
+        assert!(tree.get(six_id).is_none());
+        assert_eq!(pre_order_data(&tree), vec![0, 9]);
+        assert_eq!(tree.collect_orphans(), 0);
+    }
+
+    #[test]
+    fn split_off() {
         let mut tree = TreeBuilder::new().with_root(1).build();
 
         let two_id;
         let three_id;
         let four_id;
         let five_id;
+        let six_id;
         {
             let mut root = tree.root_mut().expect("root doesn't exist?");
             two_id = root.append(2).node_id();
@@ -1026,46 +2944,63 @@ mod tree_tests {
             four_id = root.append(4).node_id();
         }
         {
-            five_id = tree
-                .get_mut(three_id)
-                .expect("three doesn't exist?")
-                .append(5)
-                .node_id();
+            let mut three = tree.get_mut(three_id).expect("three doesn't exist?");
+            five_id = three.append(5).node_id();
+            six_id = three.append(6).node_id();
         }
 
         //        1
         //      / | \
         //     2  3  4
-        //        |
-        //        5
+        //       / \
+        //      5   6
 
-        tree.remove(three_id, OrphanChildren);
+        let (split, id_map) = tree.split_off(three_id).unwrap();
+        assert_eq!(id_map.len(), 3);
 
         let root = tree
             .get_node(tree.root_id().expect("tree doesn't exist?"))
             .unwrap();
-        assert!(root.relatives.first_child.is_some());
-        assert!(root.relatives.last_child.is_some());
-        assert_eq!(root.relatives.first_child.unwrap(), two_id);
-        assert_eq!(root.relatives.last_child.unwrap(), four_id);
-
-        let two = tree.get_node(two_id);
-        assert!(two.is_some());
-
-        let two = two.unwrap();
-        assert_eq!(two.relatives.next_sibling, Some(four_id));
-
-        let four = tree.get_node(four_id);
-        assert!(four.is_some());
+        assert_eq!(root.relatives.first_child, Some(two_id));
+        assert_eq!(root.relatives.last_child, Some(four_id));
+        assert_eq!(
+            tree.get_node(two_id).unwrap().relatives.next_sibling,
+            Some(four_id)
+        );
+        assert!(tree.get_node(three_id).is_none());
+        assert!(tree.get_node(five_id).is_none());
+        assert!(tree.get_node(six_id).is_none());
+
+        let split_root = split.root().unwrap();
+        assert_eq!(split_root.node_id(), id_map[&three_id]);
+        assert_eq!(split_root.data(), &3);
+        assert!(split_root.parent().is_none());
+        assert!(split_root.next_sibling().is_none());
+        let children: Vec<_> = split_root.children().map(|c| c.node_id()).collect();
+        assert_eq!(children, vec![id_map[&five_id], id_map[&six_id]]);
+        assert_eq!(split.capacity(), 3);
+        assert!(split.get(five_id).is_none());
+    }
 
-        let four = four.unwrap();
-        assert_eq!(four.relatives.prev_sibling, Some(two_id));
+    #[test]
+    fn split_off_root() {
+        let mut tree = TreeBuilder::new().with_root(1).build();
+        let root_id = tree.root_id().unwrap();
+        let two_id = tree.root_mut().unwrap().append(2).node_id();
 
-        let five = tree.get_node(five_id);
-        assert!(five.is_some());
+        let (split, id_map) = tree.split_off(root_id).unwrap();
+        assert!(tree.root().is_none());
+        assert!(tree.get(two_id).is_none());
+        assert_eq!(split.root().unwrap().data(), &1);
+        assert_eq!(split.get(id_map[&two_id]).unwrap().data(), &2);
+    }
 
-        let five = five.unwrap();
-        assert_eq!(five.relatives.parent, None);
+    #[test]
+    fn split_off_missing_node() {
+        let mut tree = TreeBuilder::new().with_root(1).build();
+        let two_id = tree.root_mut().unwrap().append(2).node_id();
+        tree.remove(two_id, DropChildren);
+        assert!(tree.split_off(two_id).is_none());
     }
 
     #[test]
@@ -1236,4 +3171,401 @@ mod tree_tests {
 
         assert!(tree.capacity() == 4);
     }
+
+    #[test]
+    fn drain_subtree_pre_order() {
+        let mut tree = build_retain_tree();
+        let five_id = tree.find(&5).unwrap()[0];
+
+        let drained: Vec<(usize, i32)> = tree
+            .drain_subtree(five_id, Order::Pre)
+            .unwrap()
+            .with_depth()
+            .collect();
+        assert_eq!(drained, vec![(0, 5), (1, 6), (2, 7), (1, 8)]);
+        assert_eq!(pre_order_data(&tree), vec![0, 1, 2, 3, 4, 9]);
+        assert_eq!(tree.core_tree.len(), 6);
+    }
+
+    #[test]
+    fn drain_subtree_level_order() {
+        let mut tree = build_retain_tree();
+        let root_id = tree.root_id().unwrap();
+
+        let drained: Vec<i32> = tree.drain_subtree(root_id, Order::Level).unwrap().collect();
+        assert_eq!(drained, vec![0, 1, 5, 9, 2, 6, 8, 3, 4, 7]);
+        assert!(tree.root_id().is_none());
+        assert_eq!(tree.core_tree.len(), 0);
+    }
+
+    #[test]
+    fn drain_subtree_dropped_early() {
+        let mut tree = build_retain_tree();
+        let one_id = tree.find(&1).unwrap()[0];
+
+        let mut drain = tree.drain_subtree(one_id, Order::Post).unwrap();
+        assert_eq!(drain.len(), 4);
+        assert_eq!(drain.next(), Some(3));
+        drop(drain);
+
+        assert!(tree.get(one_id).is_none());
+        assert_eq!(pre_order_data(&tree), vec![0, 5, 6, 7, 8, 9]);
+        assert_eq!(tree.core_tree.len(), 6);
+    }
+
+    #[test]
+    fn drain_subtree_missing_node() {
+        let mut tree = build_retain_tree();
+        let one_id = tree.find(&1).unwrap()[0];
+        tree.remove(one_id, DropChildren);
+
+        assert!(tree.drain_subtree(one_id, Order::Pre).is_none());
+    }
+
+    #[test]
+    fn into_iter() {
+        let tree = build_retain_tree();
+        let mut data = Vec::new();
+        for (depth, value) in tree {
+            data.push((depth, value));
+        }
+        assert_eq!(
+            data,
+            vec![
+                (0, 0),
+                (1, 1),
+                (2, 2),
+                (3, 3),
+                (3, 4),
+                (1, 5),
+                (2, 6),
+                (3, 7),
+                (2, 8),
+                (1, 9)
+            ]
+        );
+    }
+
+    #[test]
+    fn into_post_order_with_parents() {
+        let tree = build_retain_tree();
+        let records: Vec<(Option<usize>, i32)> = tree.into_post_order().with_parents().collect();
+        assert_eq!(
+            records,
+            vec![
+                (Some(2), 3),
+                (Some(2), 4),
+                (Some(3), 2),
+                (Some(9), 1),
+                (Some(5), 7),
+                (Some(7), 6),
+                (Some(7), 8),
+                (Some(9), 5),
+                (Some(9), 9),
+                (None, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn into_iter_skips_orphans() {
+        let mut tree = build_retain_tree();
+        let five_id = tree.find(&5).unwrap()[0];
+        tree.remove(five_id, OrphanChildren);
+        tree.insert_orphaned(10);
+
+        let iter = tree.into_pre_order();
+        assert_eq!(iter.len(), 6);
+        let data: Vec<i32> = iter.map(|(_, value)| value).collect();
+        assert_eq!(data, vec![0, 1, 2, 3, 4, 9]);
+
+        let tree: Tree<i32> = Tree::new();
+        assert_eq!(tree.into_iter().count(), 0);
+    }
+
+    #[test]
+    fn try_from_parents_errors() {
+        let rows = vec![("a", None, 1), ("a", Some("a"), 2)];
+        assert_eq!(
+            Tree::try_from_parents(rows).unwrap_err(),
+            BuildError::DuplicateKey("a")
+        );
+
+        let rows = vec![("a", None, 1), ("b", Some("a"), 2), ("c", None, 3)];
+        assert_eq!(
+            Tree::try_from_parents(rows).unwrap_err(),
+            BuildError::MultipleRoots("c")
+        );
+
+        let rows = vec![
+            ("a", Some("b"), 1),
+            ("b", Some("c"), 2),
+            ("c", Some("b"), 3),
+        ];
+        assert_eq!(
+            Tree::try_from_parents(rows).unwrap_err(),
+            BuildError::Cycle("b")
+        );
+
+        let rows: Vec<(&str, Option<&str>, i32)> = Vec::new();
+        assert_eq!(
+            Tree::try_from_parents(rows).unwrap_err(),
+            BuildError::NoRoot
+        );
+    }
+
+    #[test]
+    fn try_from_parents_cycle() {
+        // "d" hangs off the cycle "b" -> "c" -> "b" without being part of it
+        let rows = vec![
+            ("a", None, 1),
+            ("d", Some("b"), 4),
+            ("b", Some("c"), 2),
+            ("c", Some("b"), 3),
+        ];
+        assert_eq!(
+            Tree::try_from_parents(rows).unwrap_err(),
+            BuildError::Cycle("b")
+        );
+
+        // the first node of the list that is part of any cycle is reported
+        let rows = vec![
+            ("a", None, 1),
+            ("e", Some("f"), 5),
+            ("c", Some("b"), 3),
+            ("b", Some("c"), 2),
+            ("f", Some("e"), 6),
+        ];
+        assert_eq!(
+            Tree::try_from_parents(rows).unwrap_err(),
+            BuildError::Cycle("e")
+        );
+        let rows = vec![
+            ("d", Some("c"), 4),
+            ("c", Some("b"), 3),
+            ("b", Some("c"), 2),
+        ];
+        assert_eq!(
+            Tree::try_from_parents(rows).unwrap_err(),
+            BuildError::Cycle("c")
+        );
+
+        let rows = vec![("a", None, 1), ("b", Some("b"), 2)];
+        assert_eq!(
+            Tree::try_from_parents(rows).unwrap_err(),
+            BuildError::Cycle("b")
+        );
+    }
+
+    #[test]
+    fn try_from_parents_id_map() {
+        let rows = vec![(3, Some(1), "c"), (1, None, "a"), (2, Some(3), "b")];
+        let (tree, ids) = Tree::try_from_parents(rows).unwrap();
+        assert_eq!(ids.len(), 3);
+        assert_eq!(tree.root_id(), Some(ids[&1]));
+        assert_eq!(tree.get(ids[&2]).unwrap().data(), &"b");
+        assert_eq!(
+            tree.get(ids[&2]).unwrap().parent().unwrap().node_id(),
+            ids[&3]
+        );
+    }
+
+    #[test]
+    fn try_from_edges_errors() {
+        let nodes = || vec![(1, 'a'), (2, 'b'), (3, 'c')];
+
+        assert_eq!(
+            Tree::try_from_edges(nodes(), vec![(1, 4)]).unwrap_err(),
+            BuildError::UnknownChild(4)
+        );
+        assert_eq!(
+            Tree::try_from_edges(nodes(), vec![(4, 2)]).unwrap_err(),
+            BuildError::DanglingParent { node: 2, parent: 4 }
+        );
+        assert_eq!(
+            Tree::try_from_edges(nodes(), vec![(1, 2)]).unwrap_err(),
+            BuildError::MultipleRoots(3)
+        );
+        assert_eq!(
+            Tree::try_from_edges(vec![(1, 'a'), (1, 'b')], Vec::new()).unwrap_err(),
+            BuildError::DuplicateKey(1)
+        );
+        // the children of a cycle come in the order of their edges
+        assert_eq!(
+            Tree::try_from_edges(nodes(), vec![(2, 3), (3, 2)]).unwrap_err(),
+            BuildError::Cycle(3)
+        );
+        assert_eq!(
+            Tree::try_from_edges(nodes(), vec![(3, 1), (1, 2), (2, 3)]).unwrap_err(),
+            BuildError::Cycle(1)
+        );
+    }
+
+    #[test]
+    fn map_keeps_shape_and_orphans() {
+        let mut tree = build_retain_tree();
+        let five_id = tree.find(&5).unwrap()[0];
+        tree.remove(five_id, OrphanChildren);
+        let orphan_id = tree.insert_orphaned(10);
+        tree.get_mut(orphan_id).unwrap().append(11);
+
+        let mut seen = vec![];
+        let (mapped, id_map) = tree.map(|data| {
+            seen.push(*data);
+            data * 2
+        });
+        assert_eq!(seen.len(), 11);
+        assert_eq!(&seen[..6], &[0, 1, 2, 3, 4, 9]);
+        assert_eq!(id_map.len(), 11);
+        assert_eq!(pre_order_data(&mapped), vec![0, 2, 4, 6, 8, 18]);
+
+        let new_orphan = mapped.get(id_map[&orphan_id]).unwrap();
+        assert!(new_orphan.is_orphan());
+        assert_eq!(new_orphan.data(), &20);
+        assert_eq!(new_orphan.first_child().unwrap().data(), &22);
+        let mut orphans: Vec<i32> = mapped.orphans().map(|n| *n.data()).collect();
+        orphans.sort();
+        assert_eq!(orphans, vec![12, 16, 20]);
+
+        for (old_id, new_id) in &id_map {
+            assert_eq!(
+                tree.get(*old_id).unwrap().data() * 2,
+                *mapped.get(*new_id).unwrap().data()
+            );
+        }
+    }
+
+    #[test]
+    fn into_map_moves_data() {
+        let tree = build_retain_tree();
+        let nine_id = tree.find(&9).unwrap()[0];
+        let (mapped, id_map) = tree.into_map(|data| vec![data]);
+        assert_eq!(mapped.get(id_map[&nine_id]).unwrap().data(), &vec![9]);
+        let data: Vec<i32> = mapped
+            .root()
+            .unwrap()
+            .traverse_pre_order()
+            .map(|n| n.data()[0])
+            .collect();
+        assert_eq!(data, (0..10).collect::<Vec<_>>());
+
+        let (empty, id_map) = Tree::<i32>::new().into_map(|data| data);
+        assert!(empty.root().is_none());
+        assert!(id_map.is_empty());
+    }
+
+    #[test]
+    fn try_map_short_circuits() {
+        let tree = build_retain_tree();
+        let mut calls = 0;
+        let result = tree.try_map(|&data| {
+            calls += 1;
+            if data == 3 { Err(data) } else { Ok(data) }
+        });
+        assert_eq!(result.unwrap_err(), 3);
+        assert_eq!(calls, 4);
+
+        let (mapped, _) = tree.try_map(|&data| Ok::<_, ()>(data)).unwrap();
+        assert_eq!(pre_order_data(&mapped), (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn filter_map_prunes_subtrees() {
+        let tree = build_retain_tree();
+        let mut seen = vec![];
+        let (pruned, id_map) = tree.filter_map(|&data| {
+            seen.push(data);
+            (data != 2 && data != 5).then_some(data)
+        });
+        assert_eq!(seen, vec![0, 1, 2, 5, 9]);
+        assert_eq!(pre_order_data(&pruned), vec![0, 1, 9]);
+        assert_eq!(id_map.len(), 3);
+        assert!(!id_map.contains_key(&tree.find(&3).unwrap()[0]));
+
+        let (empty, id_map) = tree.filter_map(|_| None::<i32>);
+        assert!(empty.root().is_none());
+        assert!(id_map.is_empty());
+    }
+
+    #[test]
+    fn clone_is_compact_in_pre_order() {
+        let mut tree = build_retain_tree();
+        let one_id = tree.find(&1).unwrap()[0];
+        tree.remove(one_id, DropChildren);
+        let eight_id = tree.find(&8).unwrap()[0];
+        tree.remove(eight_id, OrphanChildren);
+        tree.get_mut(tree.root_id().unwrap()).unwrap().prepend(10);
+        let orphan_id = tree.insert_orphaned(11);
+
+        let (clone, id_map) = tree.clone_with_id_map();
+        assert_eq!(pre_order_data(&clone), vec![0, 10, 5, 6, 7, 9]);
+        let slab_order: Vec<i32> = clone.core_tree.iter().map(|(_, node)| node.data).collect();
+        assert_eq!(slab_order, vec![0, 10, 5, 6, 7, 9, 11]);
+        assert!(clone.get(id_map[&orphan_id]).unwrap().is_orphan());
+        assert!(clone.get(tree.root_id().unwrap()).is_none());
+
+        let mut clone = clone;
+        clone.root_mut().unwrap().append(12);
+        assert_eq!(pre_order_data(&tree), vec![0, 10, 5, 6, 7, 9]);
+    }
+
+    #[test]
+    fn equality_ignores_layout_and_orphans() {
+        let tree = build_retain_tree();
+        let mut other = build_retain_tree();
+        let nine_id = other.find(&9).unwrap()[0];
+        other.remove(nine_id, DropChildren);
+        other.insert_orphaned(42);
+        assert_ne!(tree, other);
+        other.root_mut().unwrap().append(9);
+        assert_eq!(tree, other);
+        assert_eq!(tree, tree.clone());
+
+        use std::hash::BuildHasher;
+        let state = std::hash::RandomState::new();
+        assert_eq!(state.hash_one(&tree), state.hash_one(&other));
+
+        let mut moved = build_retain_tree();
+        let two_id = moved.find(&2).unwrap()[0];
+        let five_id = moved.find(&5).unwrap()[0];
+        moved
+            .get_mut(two_id)
+            .unwrap()
+            .move_to(five_id, Position::First)
+            .unwrap();
+        assert_ne!(tree, moved);
+        assert_eq!(Tree::<i32>::new(), Tree::new());
+        assert_ne!(Tree::new(), tree);
+    }
+
+    #[test]
+    fn ordering() {
+        let tree = build_retain_tree();
+        let mut bigger = build_retain_tree();
+        let seven_id = bigger.find(&7).unwrap()[0];
+        bigger.get_mut(seven_id).unwrap().append(0);
+        assert!(tree < bigger);
+        assert_eq!(tree.cmp(&tree.clone()), Ordering::Equal);
+
+        let mut smaller_data = build_retain_tree();
+        let five_id = smaller_data.find(&5).unwrap()[0];
+        *smaller_data.get_mut(five_id).unwrap().data() = 4;
+        assert!(smaller_data < bigger);
+
+        let mut trees = vec![
+            bigger.clone(),
+            Tree::new(),
+            tree.clone(),
+            smaller_data.clone(),
+        ];
+        trees.sort();
+        assert_eq!(trees, vec![Tree::new(), smaller_data, tree, bigger]);
+
+        let nan = {
+            let mut tree = Tree::new();
+            tree.set_root(f64::NAN);
+            tree
+        };
+        assert_eq!(nan.partial_cmp(&nan), None);
+    }
 }